@@ -0,0 +1,88 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+/// An output from monerod's `get_outs` RPC.
+#[derive(EpeeObject, Clone)]
+struct OutKey {
+    key: [u8; 32],
+    mask: [u8; 32],
+    unlocked: bool,
+    height: u64,
+    txid: [u8; 32],
+}
+
+/// An object with only fixed width fields, the derive writes the name and marker of
+/// these fields in one write. Reading them shares the name buffer used for all fields.
+#[derive(EpeeObject, Clone)]
+struct Fixed {
+    amount: u64,
+    index: u64,
+    height: u64,
+    unlock_time: u64,
+    version: u8,
+    major_version: u16,
+    minor_version: u32,
+    unlocked: bool,
+}
+
+#[derive(EpeeObject)]
+struct Outs {
+    outs: Vec<OutKey>,
+}
+
+#[derive(EpeeObject)]
+struct FixedList {
+    list: Vec<Fixed>,
+}
+
+fn out_key(i: u64) -> OutKey {
+    OutKey {
+        key: [1; 32],
+        mask: [2; 32],
+        unlocked: true,
+        height: i,
+        txid: [3; 32],
+    }
+}
+
+fn fixed(i: u64) -> Fixed {
+    Fixed {
+        amount: i,
+        index: i,
+        height: i,
+        unlock_time: 0,
+        version: 2,
+        major_version: 16,
+        minor_version: 16,
+        unlocked: true,
+    }
+}
+
+fn bench(c: &mut Criterion) {
+    let outs = Outs {
+        outs: (0..1000).map(out_key).collect(),
+    };
+    let outs_bytes = to_bytes(&outs).unwrap();
+
+    c.bench_function("to_bytes OutKey x1000", |b| {
+        b.iter(|| to_bytes(black_box(&outs)).unwrap())
+    });
+    c.bench_function("from_bytes OutKey x1000", |b| {
+        b.iter(|| from_bytes::<Outs>(black_box(&outs_bytes)).unwrap())
+    });
+
+    let fixed = FixedList {
+        list: (0..1000).map(fixed).collect(),
+    };
+    let fixed_bytes = to_bytes(&fixed).unwrap();
+
+    c.bench_function("to_bytes Fixed x1000", |b| {
+        b.iter(|| to_bytes(black_box(&fixed)).unwrap())
+    });
+    c.bench_function("from_bytes Fixed x1000", |b| {
+        b.iter(|| from_bytes::<FixedList>(black_box(&fixed_bytes)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
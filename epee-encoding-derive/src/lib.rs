@@ -3,18 +3,34 @@
 extern crate alloc;
 
 use alloc::format;
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Ident, Literal, Span, TokenStream, TokenTree};
 use quote::quote;
 use syn::{
-    parse_macro_input, parse_quote, Data, DeriveInput, Expr, Fields, GenericParam, Generics, Lit,
-    Type,
+    ext::IdentExt, parse_macro_input, parse_quote, Data, DataEnum, DeriveInput, Expr, ExprLit,
+    Field, Fields, GenericArgument, Generics, Lit, LitStr, Member, Meta, PathArguments, Type,
+    WherePredicate,
 };
 
 #[proc_macro_derive(
     EpeeObject,
-    attributes(epee_default, epee_alt_name, epee_flatten, epee_try_from_into)
+    attributes(
+        epee,
+        epee_tag,
+        epee_default,
+        epee_default_if,
+        epee_alt_name,
+        epee_flatten,
+        epee_try_from_into,
+        epee_try_from,
+        epee_skip,
+        epee_rename_all,
+        epee_lossy_utf8,
+        epee_catch_all,
+        epee_validate
+    )
 )]
 pub fn derive_epee_object(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // Parse the input tokens into a syntax tree.
@@ -22,29 +38,972 @@ pub fn derive_epee_object(input: proc_macro::TokenStream) -> proc_macro::TokenSt
 
     let struct_name = input.ident;
 
-    let generics = add_trait_bounds(input.generics);
-    let (_impl_generics, _ty_generics, _where_clause) = generics.split_for_impl();
+    // Struct level attributes: #[epee(...)]
+    let mut all_optional = false;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("epee")) {
+        let res = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("all_optional") {
+                all_optional = true;
+                Ok(())
+            } else {
+                Err(meta.error("Unknown epee attribute"))
+            }
+        });
+        if let Err(e) = res {
+            return e.to_compile_error().into();
+        }
+    }
+
+    // Struct level attribute: #[epee_rename_all("...")]
+    let rename_all =
+        match input
+            .attrs
+            .iter()
+            .find(|a| a.path().is_ident("epee_rename_all"))
+        {
+            Some(attr) => {
+                let rule: LitStr = match attr.parse_args() {
+                    Ok(rule) => rule,
+                    Err(e) => return e.to_compile_error().into(),
+                };
+                match RenameRule::from_str(&rule.value()) {
+                    Some(rule) => Some(rule),
+                    None => return syn::Error::new(
+                        rule.span(),
+                        "Unknown casing, expected one of `snake_case`, `camelCase` or `PascalCase`",
+                    )
+                    .to_compile_error()
+                    .into(),
+                }
+            }
+            None => None,
+        };
+
+    // Struct level attribute: #[epee_validate(method)]
+    let validate: Option<Ident> = match input
+        .attrs
+        .iter()
+        .find(|a| a.path().is_ident("epee_validate"))
+        .map(|a| a.parse_args())
+        .transpose()
+    {
+        Ok(validate) => validate,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let output = match input.data {
+        Data::Struct(data) if is_newtype(&data.fields) => {
+            if all_optional || rename_all.is_some() || validate.is_some() {
+                return syn::Error::new(
+                    struct_name.span(),
+                    "Newtypes are encoded as their field so can't have object attributes",
+                )
+                .to_compile_error()
+                .into();
+            }
+            build_newtype(&data.fields, &struct_name, &input.generics)
+        }
+        Data::Struct(data) => {
+            let write_only = has_reference_field(&data.fields);
+            let generics = add_trait_bounds(
+                &input.generics,
+                field_bounds(&data.fields, &input.generics, all_optional, write_only),
+            );
+            build(
+                &data.fields,
+                &struct_name,
+                &generics,
+                all_optional,
+                rename_all,
+                validate.as_ref(),
+                write_only,
+            )
+        }
+        Data::Enum(data) => {
+            let tag: Option<LitStr> = match input
+                .attrs
+                .iter()
+                .find(|f| f.path().is_ident("epee_tag"))
+                .map(|f| f.parse_args())
+                .transpose()
+            {
+                Ok(tag) => tag,
+                Err(e) => return e.to_compile_error().into(),
+            };
+
+            let generics = add_trait_bounds(
+                &input.generics,
+                data.variants
+                    .iter()
+                    .flat_map(|variant| {
+                        field_bounds(&variant.fields, &input.generics, all_optional, false)
+                    })
+                    .collect(),
+            );
+
+            match tag {
+                Some(tag) => build_enum(
+                    &data,
+                    &struct_name,
+                    &tag,
+                    &generics,
+                    all_optional,
+                    rename_all,
+                    validate.as_ref(),
+                ),
+                None => syn::Error::new(
+                    struct_name.span(),
+                    "Enums need an `#[epee_tag(\"..\")]` attribute to be epee objects",
+                )
+                .to_compile_error(),
+            }
+        }
+        Data::Union(_) => syn::Error::new(
+            struct_name.span(),
+            "Only structs and enums can be epee objects",
+        )
+        .to_compile_error(),
+    };
+
+    output.into()
+}
+
+/// Returns true if `fields` are the fields of a tuple struct with one field, these are
+/// encoded as their field instead of as an object.
+fn is_newtype(fields: &Fields) -> bool {
+    matches!(fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1)
+}
+
+/// Builds the `EpeeValue` impl for a newtype, every method is forwarded to the field so the
+/// newtype is encoded exactly the same as the field. Newtypes are also `EpeeSeqItem`s so
+/// sequences of them are values.
+///
+/// `EpeeValue` is sealed, the seal is only exposed so this impl can be generated.
+fn build_newtype(fields: &Fields, struct_name: &Ident, generics: &Generics) -> TokenStream {
+    let field = fields.iter().next().expect("Newtypes have one field");
+    if let Some(attr) = field.attrs.iter().find(|a| {
+        a.path().segments.len() == 1 && a.path().segments[0].ident.to_string().starts_with("epee")
+    }) {
+        return syn::Error::new_spanned(
+            attr,
+            "Newtypes are encoded as their field so their field can't have epee attributes",
+        )
+        .to_compile_error();
+    }
+
+    let field_type = &field.ty;
+    let type_params: Vec<&Ident> = generics.type_params().map(|param| &param.ident).collect();
+    let mut bounds = Vec::new();
+    if uses_type_param(quote!(#field_type), &type_params) {
+        bounds.push(parse_quote!(#field_type: epee_encoding::EpeeValue));
+    }
+    let generics = add_trait_bounds(generics, bounds);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics epee_encoding::__private::EpeeValueSeal for #struct_name #ty_generics #where_clause {}
+
+        impl #impl_generics epee_encoding::EpeeValue for #struct_name #ty_generics #where_clause {
+            const MARKER: epee_encoding::Marker = <#field_type as epee_encoding::EpeeValue>::MARKER;
+            const FIXED_SIZE: Option<usize> = <#field_type as epee_encoding::EpeeValue>::FIXED_SIZE;
+
+            fn read<__EpeeR: epee_encoding::io::Read>(r: &mut __EpeeR, marker: &epee_encoding::Marker) -> epee_encoding::error::Result<Self> {
+                Ok(Self(<#field_type as epee_encoding::EpeeValue>::read(r, marker)?))
+            }
+
+            fn should_write(&self) -> bool {
+                epee_encoding::EpeeValue::should_write(&self.0)
+            }
+
+            fn epee_default_value() -> Option<Self> {
+                <#field_type as epee_encoding::EpeeValue>::epee_default_value().map(Self)
+            }
+
+            fn marker(&self) -> epee_encoding::Marker {
+                epee_encoding::EpeeValue::marker(&self.0)
+            }
+
+            fn read_into<__EpeeR: epee_encoding::io::Read>(&mut self, r: &mut __EpeeR, marker: &epee_encoding::Marker) -> epee_encoding::error::Result<()> {
+                epee_encoding::EpeeValue::read_into(&mut self.0, r, marker)
+            }
+
+            fn write<__EpeeW: epee_encoding::io::Write>(&self, w: &mut __EpeeW) -> epee_encoding::error::Result<()> {
+                epee_encoding::EpeeValue::write(&self.0, w)
+            }
+        }
+
+        impl #impl_generics epee_encoding::EpeeSeqItem for #struct_name #ty_generics #where_clause {}
+    }
+}
+
+/// Returns `T` if `ty` is `Option<T>`.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Adds `bounds` to the where clause of `generics`.
+fn add_trait_bounds(generics: &Generics, bounds: Vec<WherePredicate>) -> Generics {
+    let mut generics = generics.clone();
+    generics.make_where_clause().predicates.extend(bounds);
+    generics
+}
+
+/// Returns the bounds needed on the types of `fields` which use a type parameter of
+/// `generics`, fields which don't use a type parameter don't need bounds.
+///
+/// Bounds are added on the field types instead of the type parameters so a parameter
+/// only used in a skipped field or in a `PhantomData` is not required to be an `EpeeValue`.
+/// If the object is `write_only` flattened fields only need to be written.
+fn field_bounds(
+    fields: &Fields,
+    generics: &Generics,
+    all_optional: bool,
+    write_only: bool,
+) -> Vec<WherePredicate> {
+    let type_params: Vec<&Ident> = generics.type_params().map(|param| &param.ident).collect();
+
+    let mut bounds = Vec::new();
+    for field in fields.iter() {
+        let ty = &field.ty;
+        if !uses_type_param(quote!(#ty), &type_params) {
+            continue;
+        }
+
+        let has_attr = |name: &str| field.attrs.iter().any(|f| f.path().is_ident(name));
+
+        if is_skipped(field) {
+            bounds.push(parse_quote!(#ty: Default));
+        } else if has_attr("epee_flatten") {
+            let ty = option_inner_type(ty).unwrap_or(ty);
+            if write_only {
+                bounds.push(parse_quote!(#ty: epee_encoding::EpeeObjectWrite));
+            } else {
+                bounds.push(parse_quote!(#ty: epee_encoding::EpeeObject));
+            }
+        } else if let Type::Reference(reference) = ty {
+            let elem = &reference.elem;
+            bounds.push(parse_quote!(#elem: epee_encoding::EpeeObjectWrite));
+        } else if !has_attr("epee_try_from_into")
+            && !has_attr("epee_try_from")
+            && !has_attr("epee_catch_all")
+        {
+            bounds.push(parse_quote!(#ty: epee_encoding::EpeeValue));
+            let bare_default = field
+                .attrs
+                .iter()
+                .any(|f| f.path().is_ident("epee_default") && matches!(f.meta, Meta::Path(_)));
+            let has_default = has_attr("epee_default");
+            if has_attr("epee_default_if") && (bare_default || !has_default) {
+                bounds.push(parse_quote!(#ty: Default));
+            } else if bare_default {
+                bounds.push(parse_quote!(#ty: Default + PartialEq));
+            } else if all_optional {
+                bounds.push(parse_quote!(#ty: Default));
+            }
+        }
+    }
+    bounds
+}
+
+/// Returns true if `field` is not encoded, either because it has `#[epee_skip]` or
+/// because it is a `PhantomData`.
+fn is_skipped(field: &Field) -> bool {
+    let is_phantom = match &field.ty {
+        Type::Path(path) => {
+            matches!(path.path.segments.last(), Some(segment) if segment.ident == "PhantomData")
+        }
+        _ => false,
+    };
+    is_phantom || field.attrs.iter().any(|f| f.path().is_ident("epee_skip"))
+}
+
+/// Returns true if any encoded, non-flattened field in `fields` is a reference, objects with
+/// reference fields can only be written so only `EpeeObjectWrite` is implemented for them.
+fn has_reference_field(fields: &Fields) -> bool {
+    fields.iter().any(|field| {
+        matches!(field.ty, Type::Reference(_))
+            && !is_skipped(field)
+            && !field
+                .attrs
+                .iter()
+                .any(|f| f.path().is_ident("epee_flatten"))
+    })
+}
+
+/// Returns true if `ty` is a `Vec` or `String`, values of these types are taken from
+/// the old object given to the builder's `reuse` so their buffers can be read into.
+fn is_reusable(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => matches!(
+            path.path.segments.last(),
+            Some(seg) if seg.ident == "Vec" || seg.ident == "String"
+        ),
+        _ => false,
+    }
+}
+
+/// Returns the size of a field called `name` with a value of `size` bytes, including
+/// the length of the name and the marker.
+fn field_size_hint(name: &str, size: usize) -> usize {
+    1 + name.len() + 1 + size
+}
+
+/// Returns true if any of the identifiers in `tokens` are one of `type_params`.
+fn uses_type_param(tokens: TokenStream, type_params: &[&Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => type_params.iter().any(|param| **param == ident),
+        TokenTree::Group(group) => uses_type_param(group.stream(), type_params),
+        _ => false,
+    })
+}
+
+/// The casing applied to field names by `#[epee_rename_all("...")]`.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Snake,
+    Camel,
+    Pascal,
+}
+
+impl RenameRule {
+    fn from_str(rule: &str) -> Option<Self> {
+        match rule {
+            "snake_case" => Some(RenameRule::Snake),
+            "camelCase" => Some(RenameRule::Camel),
+            "PascalCase" => Some(RenameRule::Pascal),
+            _ => None,
+        }
+    }
+
+    /// Applies this rule to a Rust field name, which is expected to be snake case.
+    fn apply(self, field_name: &str) -> String {
+        match self {
+            RenameRule::Snake => field_name.to_string(),
+            RenameRule::Camel | RenameRule::Pascal => {
+                let mut name = String::new();
+                let mut capitalize = matches!(self, RenameRule::Pascal);
+                for c in field_name.chars() {
+                    if c == '_' {
+                        capitalize = !name.is_empty();
+                    } else if capitalize {
+                        name.extend(c.to_uppercase());
+                        capitalize = false;
+                    } else {
+                        name.push(c);
+                    }
+                }
+                name
+            }
+        }
+    }
+}
+
+/// The generated code for the fields of a struct or enum variant.
+struct BuiltFields {
+    /// The fields of the builder struct.
+    struct_fields: TokenStream,
+    /// The default values of the builder struct's fields.
+    default_values: TokenStream,
+    /// Takes away from `numb_o_fields` for fields that will not be written.
+    count_fields: TokenStream,
+    /// Writes the fields.
+    write_fields: TokenStream,
+    /// The match arms for fields in `add_field`.
+    read_match_body: TokenStream,
+    /// Called in `add_field` for fields not matched by `read_match_body`.
+    read_catch_all: TokenStream,
+    /// The fields of the object being built in `finish`.
+    object_finish: TokenStream,
+    /// Takes the fields of `old` that can be reused in `reuse`.
+    reuse_fields: TokenStream,
+    /// The builder's fields added to a `DebugStruct`, only used with `debug-builders`.
+    debug_fields: TokenStream,
+    /// The number of fields before fields are taken away in `count_fields`.
+    numb_o_fields: u64,
+    /// An estimate of the number of bytes the fields will be encoded with.
+    size_hint: TokenStream,
+}
+
+fn build(
+    fields: &Fields,
+    struct_name: &Ident,
+    generics: &Generics,
+    all_optional: bool,
+    rename_all: Option<RenameRule>,
+    validate: Option<&Ident>,
+    write_only: bool,
+) -> TokenStream {
+    let BuiltFields {
+        struct_fields,
+        default_values,
+        count_fields,
+        write_fields,
+        read_match_body,
+        read_catch_all,
+        object_finish,
+        reuse_fields,
+        debug_fields,
+        numb_o_fields,
+        size_hint,
+    } = match build_fields(
+        fields,
+        all_optional,
+        rename_all,
+        None,
+        |member| quote!(self.#member),
+    ) {
+        Ok(built) => built,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let builder_name = Ident::new(&format!("__{}EpeeBuilder", struct_name), Span::call_site());
+    let mod_name = Ident::new(&format!("__{}_epee_module", struct_name), Span::call_site());
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let reuse = if reuse_fields.is_empty() {
+        TokenStream::new()
+    } else {
+        quote! {
+            fn reuse(&mut self, old: &mut #struct_name #ty_generics) {
+                #reuse_fields
+            }
+        }
+    };
+
+    let validate_object = validate_object(validate);
+
+    let debug_name = builder_name.to_string();
+    let debug_impl = builder_debug_impl(
+        &builder_name,
+        generics,
+        quote! {
+            f.debug_struct(#debug_name)
+                #debug_fields
+                .finish()
+        },
+    );
+
+    let builder_impl = quote! {
+        // The builder's fields have the same names as the struct's fields.
+        #[allow(non_snake_case)]
+        pub struct #builder_name #impl_generics #where_clause {
+            #struct_fields
+            __epee_phantom: core::marker::PhantomData<fn() -> #struct_name #ty_generics>,
+        }
+
+        impl #impl_generics Default for #builder_name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #default_values
+                    __epee_phantom: core::marker::PhantomData,
+                }
+            }
+        }
+
+        impl #impl_generics epee_encoding::EpeeObjectBuilder<#struct_name #ty_generics> for #builder_name #ty_generics #where_clause {
+            // Structs without fields never return `Ok(true)`.
+            #[allow(unreachable_code)]
+            fn add_field<__EpeeR: epee_encoding::io::Read>(&mut self, name: &str, r: &mut __EpeeR) -> epee_encoding::error::Result<bool> {
+                match name {
+                    #read_match_body
+                    _ => {
+                        #read_catch_all
+                        return Ok(false);
+                    }
+                };
+
+                Ok(true)
+            }
+
+            fn finish(self) -> epee_encoding::error::Result<#struct_name #ty_generics> {
+                let object = #struct_name {
+                    #object_finish
+                };
+                #validate_object
+                Ok(object)
+            }
+
+            #reuse
+        }
+
+        #debug_impl
+    };
+
+    let write_impl = quote! {
+        impl #impl_generics epee_encoding::EpeeObjectWrite for #struct_name #ty_generics #where_clause {
+            fn number_of_fields(&self) -> u64 {
+                let mut numb_o_fields: u64 = #numb_o_fields;
+                #count_fields
+                numb_o_fields
+            }
+
+            fn size_hint(&self) -> usize {
+                #size_hint
+            }
+
+
+            fn write_fields<__EpeeW: epee_encoding::io::Write>(&self, w: &mut __EpeeW) -> epee_encoding::error::Result<()> {
+
+                #write_fields
+
+                Ok(())
+            }
+        }
+    };
+
+    // Objects with reference fields can't be built so they have no builder.
+    if write_only {
+        return write_impl;
+    }
+
+    quote! {
+        mod #mod_name {
+            use super::*;
+            #builder_impl
+        }
+
+        impl #impl_generics EpeeObject for #struct_name #ty_generics #where_clause {
+            type Builder = #mod_name::#builder_name #ty_generics;
+        }
+
+        #write_impl
+    }
+}
+
+/// Returns a `Debug` impl for the builder `name` with the `debug-builders` feature, so
+/// partially read objects can be inspected.
+///
+/// The impl has no extra bounds, features are unified across crates so requiring the fields
+/// to implement `Debug` would break crates which don't enable the feature. Fields are shown
+/// as whether they have been read instead of their values.
+fn builder_debug_impl(name: &Ident, generics: &Generics, body: TokenStream) -> TokenStream {
+    if !cfg!(feature = "debug-builders") {
+        return TokenStream::new();
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics core::fmt::Debug for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                #body
+            }
+        }
+    }
+}
+
+/// Returns the code to call the `#[epee_validate(method)]` method on `object` after
+/// it has been built.
+fn validate_object(validate: Option<&Ident>) -> TokenStream {
+    match validate {
+        Some(method) => quote! {
+            object.#method()?;
+        },
+        None => TokenStream::new(),
+    }
+}
+
+/// Builds an enum, each variant is encoded as an object with the variant's tag
+/// written under the `tag` field followed by the variant's fields.
+///
+/// A variant's tag is its discriminant, which must be an integer literal that fits in
+/// a `u8`. Like Rust discriminants, a variant without one is tagged one more than the
+/// previous variant.
+fn build_enum(
+    data: &DataEnum,
+    enum_name: &Ident,
+    tag: &LitStr,
+    generics: &Generics,
+    all_optional: bool,
+    rename_all: Option<RenameRule>,
+    validate: Option<&Ident>,
+) -> TokenStream {
+    let builder_name = Ident::new(&format!("__{}EpeeBuilder", enum_name), Span::call_site());
+    let mod_name = Ident::new(&format!("__{}_epee_module", enum_name), Span::call_site());
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // The tag is written as a field name so has the same limits.
+    let tag_len = tag.value().len();
+    if tag_len == 0 || tag_len > 255 {
+        return syn::Error::new_spanned(
+            tag,
+            format!(
+                "The epee tag is {} bytes long, field names must be between 1 and 255 bytes long",
+                tag_len
+            ),
+        )
+        .to_compile_error();
+    }
+
+    let tag_size_hint = field_size_hint(&tag.value(), 1);
+
+    // The tag of the next variant if it has no discriminant, and the tags already used.
+    let mut next_tag: u16 = 0;
+    let mut used_tags = Vec::new();
+
+    let mut variant_builders = TokenStream::new();
+    let mut builder_variants = TokenStream::new();
+    let mut tag_match = TokenStream::new();
+    let mut add_field_arms = TokenStream::new();
+    let mut finish_arms = TokenStream::new();
+    let mut count_arms = TokenStream::new();
+    let mut size_hint_arms = TokenStream::new();
+    let mut write_arms = TokenStream::new();
+    let mut debug_arms = TokenStream::new();
+
+    for variant in data.variants.iter() {
+        let variant_name = &variant.ident;
+
+        if has_reference_field(&variant.fields) {
+            return syn::Error::new_spanned(
+                variant,
+                "Enum variants can't have reference fields as enums can't be write only",
+            )
+            .to_compile_error();
+        }
+
+        let variant_tag: u8 = match &variant.discriminant {
+            Some((
+                _,
+                Expr::Lit(ExprLit {
+                    lit: Lit::Int(int), ..
+                }),
+            )) => match int.base10_parse() {
+                Ok(tag) => tag,
+                Err(_) => {
+                    return syn::Error::new_spanned(int, "Epee enum tags must fit in a `u8`")
+                        .to_compile_error()
+                }
+            },
+            Some((_, discriminant)) => {
+                return syn::Error::new_spanned(
+                    discriminant,
+                    "Epee enum discriminants must be integer literals",
+                )
+                .to_compile_error()
+            }
+            None => match next_tag.try_into() {
+                Ok(tag) => tag,
+                Err(_) => {
+                    return syn::Error::new_spanned(variant, "Epee enum tags must fit in a `u8`")
+                        .to_compile_error()
+                }
+            },
+        };
+        if used_tags.contains(&variant_tag) {
+            return syn::Error::new_spanned(
+                variant,
+                format!("The epee tag {} is used by another variant", variant_tag),
+            )
+            .to_compile_error();
+        }
+        used_tags.push(variant_tag);
+        next_tag = u16::from(variant_tag) + 1;
+        let variant_tag = Literal::u8_suffixed(variant_tag);
+
+        // The fields are bound to different names so they can't shadow anything
+        // in the generated code, unnamed fields are bound to their index.
+        let binding = |member: &Member| match member {
+            Member::Named(field_name) => {
+                Ident::new(&format!("__epee_{}", field_name.unraw()), Span::call_site())
+            }
+            Member::Unnamed(index) => {
+                Ident::new(&format!("__epee_{}", index.index), Span::call_site())
+            }
+        };
+
+        let BuiltFields {
+            struct_fields,
+            default_values,
+            count_fields,
+            write_fields,
+            read_match_body,
+            read_catch_all,
+            object_finish,
+            // The variant being decoded is not known in `reuse` so enums don't reuse values.
+            reuse_fields: _,
+            debug_fields,
+            numb_o_fields,
+            size_hint,
+        } = match build_fields(
+            &variant.fields,
+            all_optional,
+            rename_all,
+            Some(tag),
+            |member| {
+                let binding = binding(member);
+                quote!((*#binding))
+            },
+        ) {
+            Ok(built) => built,
+            Err(e) => return e.to_compile_error(),
+        };
+
+        // Unnamed fields are matched by index, `Enum::Variant { 0: __epee_0 }`, so every
+        // variant uses the same pattern.
+        let members: Vec<Member> = variant
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| match &f.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(i.into()),
+            })
+            .collect();
+        let bindings = members.iter().map(binding);
+        let pattern = quote! {
+            #enum_name::#variant_name { #(#members: #bindings),* }
+        };
+
+        let variant_builder_name = Ident::new(
+            &format!("__{}{}EpeeBuilder", enum_name, variant_name),
+            Span::call_site(),
+        );
+
+        variant_builders = quote! {
+            #variant_builders
+
+            #[allow(non_snake_case)]
+            pub struct #variant_builder_name #impl_generics #where_clause {
+                #struct_fields
+                __epee_phantom: core::marker::PhantomData<fn() -> #enum_name #ty_generics>,
+            }
+
+            impl #impl_generics Default for #variant_builder_name #ty_generics #where_clause {
+                fn default() -> Self {
+                    Self {
+                        #default_values
+                        __epee_phantom: core::marker::PhantomData,
+                    }
+                }
+            }
+
+            impl #impl_generics epee_encoding::EpeeObjectBuilder<#enum_name #ty_generics> for #variant_builder_name #ty_generics #where_clause {
+                // Variants without fields never return `Ok(true)`.
+                #[allow(unreachable_code)]
+                fn add_field<__EpeeR: epee_encoding::io::Read>(&mut self, name: &str, r: &mut __EpeeR) -> epee_encoding::error::Result<bool> {
+                    match name {
+                        #read_match_body
+                        _ => {
+                            #read_catch_all
+                            return Ok(false);
+                        }
+                    };
+
+                    Ok(true)
+                }
+
+                fn finish(self) -> epee_encoding::error::Result<#enum_name #ty_generics> {
+                    Ok(#enum_name::#variant_name {
+                        #object_finish
+                    })
+                }
+            }
+        };
+
+        let debug_name = variant_builder_name.to_string();
+        let debug_impl = builder_debug_impl(
+            &variant_builder_name,
+            generics,
+            quote! {
+                f.debug_struct(#debug_name)
+                    #debug_fields
+                    .finish()
+            },
+        );
+        variant_builders = quote! {
+            #variant_builders
+            #debug_impl
+        };
+
+        let debug_variant_name = variant_name.to_string();
+        debug_arms = quote! {
+            #debug_arms
+            Self::#variant_name(builder) => f.debug_tuple(#debug_variant_name).field(builder).finish(),
+        };
+
+        builder_variants = quote! {
+            #builder_variants
+            #variant_name(#variant_builder_name #ty_generics),
+        };
+
+        tag_match = quote! {
+            #tag_match
+            #variant_tag => Self::#variant_name(Default::default()),
+        };
+
+        add_field_arms = quote! {
+            #add_field_arms
+            Self::#variant_name(builder) => epee_encoding::EpeeObjectBuilder::add_field(builder, name, r),
+        };
+
+        finish_arms = quote! {
+            #finish_arms
+            Self::#variant_name(builder) => epee_encoding::EpeeObjectBuilder::finish(builder),
+        };
+
+        count_arms = quote! {
+            #count_arms
+            #pattern => {
+                // The tag is always written.
+                #[allow(unused_mut)]
+                let mut numb_o_fields: u64 = #numb_o_fields + 1;
+                #count_fields
+                numb_o_fields
+            }
+        };
+
+        size_hint_arms = quote! {
+            #size_hint_arms
+            #pattern => #tag_size_hint + #size_hint,
+        };
+
+        write_arms = quote! {
+            #write_arms
+            #pattern => {
+                epee_encoding::write_field(&#variant_tag, #tag, w)?;
+                #write_fields
+            }
+        };
+    }
+
+    let missing_tag = format!("Required field `{}` was not found!", tag.value());
+    let validate_object = validate_object(validate);
+
+    let debug_impl = builder_debug_impl(
+        &builder_name,
+        generics,
+        quote! {
+            match self {
+                Self::__EpeeNoTag(..) => f.write_str("__EpeeNoTag"),
+                #debug_arms
+            }
+        },
+    );
+
+    let builder_impl = quote! {
+        #variant_builders
+
+        pub enum #builder_name #impl_generics #where_clause {
+            __EpeeNoTag(
+                epee_encoding::__private::PendingFields,
+                core::marker::PhantomData<fn() -> #enum_name #ty_generics>,
+            ),
+            #builder_variants
+        }
+
+        impl #impl_generics Default for #builder_name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self::__EpeeNoTag(Default::default(), core::marker::PhantomData)
+            }
+        }
+
+        impl #impl_generics epee_encoding::EpeeObjectBuilder<#enum_name #ty_generics> for #builder_name #ty_generics #where_clause {
+            fn add_field<__EpeeR: epee_encoding::io::Read>(&mut self, name: &str, r: &mut __EpeeR) -> epee_encoding::error::Result<bool> {
+                if name == #tag {
+                    let pending = match self {
+                        Self::__EpeeNoTag(pending, _) => core::mem::take(pending),
+                        _ => return Err(epee_encoding::error::Error::Format("Double key in data!")),
+                    };
+
+                    let tag: u8 = epee_encoding::read_epee_value(r)?;
+                    *self = match tag {
+                        #tag_match
+                        _ => return Err(epee_encoding::error::Error::Format("Unknown enum tag")),
+                    };
+                    // Now the variant is known the fields before the tag can be read.
+                    pending.replay(self, r)?;
+                    return Ok(true);
+                }
+
+                match self {
+                    Self::__EpeeNoTag(pending, _) => {
+                        pending.push(name, r)?;
+                        Ok(true)
+                    }
+                    #add_field_arms
+                }
+            }
+
+            fn finish(self) -> epee_encoding::error::Result<#enum_name #ty_generics> {
+                let object = match self {
+                    Self::__EpeeNoTag(..) => Err(epee_encoding::error::Error::Format(#missing_tag)),
+                    #finish_arms
+                }?;
+                #validate_object
+                Ok(object)
+            }
+        }
+
+        #debug_impl
+    };
+
+    let object_impl = quote! {
+        impl #impl_generics EpeeObject for #enum_name #ty_generics #where_clause {
+            type Builder = #mod_name::#builder_name #ty_generics;
+        }
 
-    let output = match input.data {
-        Data::Struct(data) => build(&data.fields, &struct_name),
-        _ => panic!("Only structs can be epee objects"),
-    };
+        impl #impl_generics epee_encoding::EpeeObjectWrite for #enum_name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn number_of_fields(&self) -> u64 {
+                match self {
+                    #count_arms
+                }
+            }
 
-    output.into()
-}
+            #[allow(unused_variables)]
+            fn size_hint(&self) -> usize {
+                match self {
+                    #size_hint_arms
+                }
+            }
+
+            fn write_fields<__EpeeW: epee_encoding::io::Write>(&self, w: &mut __EpeeW) -> epee_encoding::error::Result<()> {
+                match self {
+                    #write_arms
+                }
+
+                Ok(())
+            }
+        }
+    };
 
-fn add_trait_bounds(mut generics: Generics) -> Generics {
-    for param in &mut generics.params {
-        if let GenericParam::Type(ref mut type_param) = *param {
-            type_param
-                .bounds
-                .push(parse_quote!(epee_encoding::EpeeValue));
+    quote! {
+        mod #mod_name {
+            use super::*;
+            #builder_impl
         }
+
+        #object_impl
     }
-    generics
 }
 
-fn build(fields: &Fields, struct_name: &Ident) -> TokenStream {
+/// Generates the code for `fields`, `field_access` returns the expression to access
+/// a field when writing. `tag` is the name of the tag field written with the fields of
+/// an enum variant.
+fn build_fields(
+    fields: &Fields,
+    all_optional: bool,
+    rename_all: Option<RenameRule>,
+    tag: Option<&LitStr>,
+    field_access: impl Fn(&Member) -> TokenStream,
+) -> syn::Result<BuiltFields> {
     let mut struct_fields = TokenStream::new();
     let mut default_values = TokenStream::new();
     let mut count_fields = TokenStream::new();
@@ -55,89 +1014,365 @@ fn build(fields: &Fields, struct_name: &Ident) -> TokenStream {
 
     let mut object_finish = TokenStream::new();
 
-    let numb_o_fields: u64 = fields.len().try_into().unwrap();
+    let mut reuse_fields = TokenStream::new();
+    let mut debug_fields = TokenStream::new();
 
-    for field in fields {
-        let field_name = field.ident.clone().expect("Epee only accepts named fields");
+    let mut size_hint = quote!(0);
+
+    // The code to store unknown fields in the catch-all field, this has to be after
+    // the flattened fields so it is only added after every field is built.
+    let mut catch_all_field = None;
+    // The names of the fields written by this object, not including flattened fields, the
+    // catch-all field doesn't write fields with these names as they would be written twice.
+    let mut known_names: Vec<TokenStream> = tag.iter().map(|tag| quote!(#tag)).collect();
+
+    let numb_o_fields: u64 = fields
+        .iter()
+        .filter(|field| !is_skipped(field))
+        .count()
+        .try_into()
+        .unwrap();
+
+    for (i, field) in fields.iter().enumerate() {
+        // Tuple struct fields are named by their index.
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        };
+        // The name of this field in the builder.
+        let field_name = match &field.ident {
+            Some(ident) => ident.clone(),
+            None => Ident::new(&format!("__field_{}", i), Span::call_site()),
+        };
         let field_type = &field.ty;
-        // If this field has a default value find it
-        let default_val: Option<Expr> = field
+        let field_access = field_access(&member);
+        let debug_name = field_name.unraw().to_string();
+
+        // Skipped fields are not encoded and are set to their default value when decoding,
+        // `PhantomData` fields are always skipped.
+        if is_skipped(field) {
+            object_finish = quote! {
+                #object_finish
+                #member: Default::default(),
+            };
+            continue;
+        }
+
+        // Unknown fields are stored in a catch-all field and re-written after the other fields.
+        if field
             .attrs
             .iter()
-            .find(|f| f.path().is_ident("epee_default"))
-            .map(|f| f.parse_args().unwrap());
+            .any(|f| f.path().is_ident("epee_catch_all"))
+        {
+            if catch_all_field.is_some() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "Only one field can be a catch-all field",
+                ));
+            }
+            catch_all_field = Some(quote! {
+                let val: epee_encoding::Value = epee_encoding::read_epee_value(r)?;
+                if self.#field_name.insert(name.into(), val).is_some() {
+                    return Err(epee_encoding::error::Error::Format("Double key in data!"));
+                }
+                return Ok(true);
+            });
+
+            struct_fields = quote! {
+                #struct_fields
+                #field_name: #field_type,
+            };
+            debug_fields = quote! {
+                #debug_fields
+                .field(#debug_name, &format_args!("{} fields", self.#field_name.len()))
+            };
+            default_values = quote! {
+                #default_values
+                #field_name: Default::default(),
+            };
+            count_fields = quote! {
+                #count_fields
+                // Every stored field is written instead of this field.
+                numb_o_fields -= 1;
+                numb_o_fields += #field_access
+                    .iter()
+                    .filter(|(name, _)| {
+                        let name: &str = name;
+                        !__epee_known_names.contains(&name)
+                    })
+                    .count() as u64;
+            };
+            write_fields = quote! {
+                #write_fields
+                for (name, val) in #field_access.iter() {
+                    let name: &str = name;
+                    if __epee_known_names.contains(&name) {
+                        continue;
+                    }
+                    epee_encoding::write_field::<epee_encoding::Value, _>(val, name, w)?;
+                }
+            };
+            object_finish = quote! {
+                #object_finish
+                #member: self.#field_name,
+            };
+            continue;
+        }
+
         // If this field has a different name when encoded find it
-        let alt_name: Option<Lit> = field
+        let alt_name: Option<Expr> = field
             .attrs
             .iter()
             .find(|f| f.path().is_ident("epee_alt_name"))
-            .map(|f| f.parse_args().unwrap());
+            .map(|f| f.parse_args())
+            .transpose()?;
 
         let is_flattened = field
             .attrs
             .iter()
             .any(|f| f.path().is_ident("epee_flatten"));
 
+        // `epee_try_from` is an alias of `epee_try_from_into`.
         let try_from_into: Option<Type> = field
             .attrs
             .iter()
-            .find(|f| f.path().is_ident("epee_try_from_into"))
-            .map(|f| f.parse_args().unwrap());
+            .find(|f| f.path().is_ident("epee_try_from_into") || f.path().is_ident("epee_try_from"))
+            .map(|f| f.parse_args())
+            .transpose()?;
+
+        let is_lossy_utf8 = field
+            .attrs
+            .iter()
+            .any(|f| f.path().is_ident("epee_lossy_utf8"));
+
+        // A predicate deciding if the field is the default, instead of comparing it to
+        // the default value.
+        let default_if: Option<Expr> = field
+            .attrs
+            .iter()
+            .find(|f| f.path().is_ident("epee_default_if"))
+            .map(|f| f.parse_args())
+            .transpose()?;
+
+        // If this field has a default value find it, a bare `#[epee_default]` uses the
+        // `Default` impl of the type the field is encoded as, as does `#[epee_default_if]`
+        // without an `#[epee_default]`.
+        let bare_default = || {
+            let ty = try_from_into.as_ref().unwrap_or(field_type);
+            parse_quote!(<#ty as Default>::default())
+        };
+        let default_val: Option<Expr> = field
+            .attrs
+            .iter()
+            .find(|f| f.path().is_ident("epee_default"))
+            .map(|f| match &f.meta {
+                Meta::Path(_) => Ok(bare_default()),
+                _ => f.parse_args(),
+            })
+            .transpose()?
+            .or_else(|| default_if.as_ref().map(|_| bare_default()));
 
-        // Gets this objects epee name, the name its encoded with
+        // Gets this objects epee name, the name its encoded with. If the name is a path
+        // to a constant `name_const` is the path and `epee_name` is only used in messages.
+        let mut name_const = None;
         let epee_name = if let Some(alt) = alt_name {
             if is_flattened {
-                panic!("Cant rename a flattened field")
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "Cant rename a flattened field",
+                ));
             }
             match alt {
-                Lit::Str(name) => name.value(),
-                _ => panic!("Alt name was not a string"),
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(name),
+                    ..
+                }) => name.value(),
+                Expr::Lit(ExprLit {
+                    lit: Lit::ByteStr(name),
+                    ..
+                }) => String::from_utf8(name.value())
+                    .map_err(|_| syn::Error::new_spanned(&name, "Alt name is not valid UTF-8"))?,
+                Expr::Path(path) => {
+                    let name = quote!(#path).to_string();
+                    name_const = Some(path);
+                    name
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        alt,
+                        "Alt name must be a string, a byte string or a path to a `&str` constant",
+                    ))
+                }
             }
         } else {
-            field_name.to_string()
+            match (&member, rename_all) {
+                (Member::Named(ident), Some(rule)) => rule.apply(&ident.unraw().to_string()),
+                (Member::Named(ident), None) => ident.unraw().to_string(),
+                (Member::Unnamed(index), _) => index.index.to_string(),
+            }
+        };
+
+        // Field names are written with a one byte length and can't be empty, this is
+        // checked after `epee_alt_name` and `epee_rename_all` are applied.
+        if name_const.is_none() && (epee_name.is_empty() || epee_name.len() > 255) {
+            return Err(syn::Error::new_spanned(
+                field,
+                format!(
+                    "The epee name of this field is {} bytes long, field names must be between 1 and 255 bytes long",
+                    epee_name.len()
+                ),
+            ));
+        }
+
+        // The name used in the generated code.
+        let name_tokens = match &name_const {
+            Some(path) => quote!(#path),
+            None => quote!(#epee_name),
         };
+        if !is_flattened {
+            known_names.push(name_tokens.clone());
+        }
 
         if try_from_into.is_some() && is_flattened {
-            panic!("Cant flatten this field: {}", field_name);
+            return Err(syn::Error::new_spanned(
+                field,
+                "Cant flatten a field with `epee_try_from_into`",
+            ));
+        }
+
+        if is_lossy_utf8 && (try_from_into.is_some() || is_flattened) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "Cant use `epee_lossy_utf8` on a flattened field or a field with `epee_try_from_into`",
+            ));
+        }
+
+        // A flattened `Option<T>` is `None` if none of `T`'s fields are in the data.
+        let flattened_option = if is_flattened {
+            option_inner_type(field_type)
+        } else {
+            None
+        };
+
+        // Reference fields are written through `Borrowed` so they don't need to be
+        // `EpeeValue`s, this supports `&str`, `&[u8]` and references to objects.
+        let (write_type, write_access) = match field_type {
+            Type::Reference(reference) if !is_flattened => {
+                let elem = &reference.elem;
+                (
+                    quote!(epee_encoding::__private::Borrowed<'_, #elem>),
+                    quote!(epee_encoding::__private::Borrowed::<#elem>(#field_access)),
+                )
+            }
+            _ => (quote!(#field_type), field_access.clone()),
+        };
+
+        // Only fields with a fixed size and flattened fields are counted in the size hint.
+        if flattened_option.is_some() {
+            size_hint = quote! {
+                #size_hint + match &#field_access {
+                    Some(val) => epee_encoding::EpeeObjectWrite::size_hint(val),
+                    None => 0,
+                }
+            };
+        } else if is_flattened {
+            size_hint = quote! {
+                #size_hint + epee_encoding::EpeeObjectWrite::size_hint(&#field_access)
+            };
+        } else {
+            let written_type = match &try_from_into {
+                Some(try_from_into) => quote!(#try_from_into),
+                None => write_type.clone(),
+            };
+            let field_size = match &name_const {
+                Some(path) => quote!(1 + #path.len() + 1 + size),
+                None => {
+                    let name_size = field_size_hint(&epee_name, 0);
+                    quote!(#name_size + size)
+                }
+            };
+            size_hint = quote! {
+                #size_hint + match <#written_type as epee_encoding::EpeeValue>::FIXED_SIZE {
+                    Some(size) => #field_size,
+                    None => 0,
+                }
+            };
         }
 
         // This is fields part of a struct:
         // struct T {
         //  #struct_fields
         // }
-        if is_flattened {
-            struct_fields = quote! {
-                #struct_fields
-                #field_name: <#field_type as epee_encoding::EpeeObject>::Builder,
-            };
+        let builder_field_type = if let Some(inner_type) = flattened_option {
+            quote!((<#inner_type as epee_encoding::EpeeObject>::Builder, bool))
+        } else if is_flattened {
+            quote!(<#field_type as epee_encoding::EpeeObject>::Builder)
+        } else if let Some(try_from_into) = &try_from_into {
+            quote!((Option<#try_from_into>, bool))
+        } else {
+            quote!((Option<#field_type>, bool))
+        };
+        struct_fields = quote! {
+            #struct_fields
+            #field_name: #builder_field_type,
+        };
+        let debug_state = if is_flattened && flattened_option.is_none() {
+            quote!("flattened")
+        } else {
+            quote!(if self.#field_name.1 { "read" } else { "not read" })
+        };
+        debug_fields = quote! {
+            #debug_fields
+            .field(#debug_name, &format_args!("{}", #debug_state))
+        };
 
+        if flattened_option.is_some() {
+            count_fields = quote! {
+                #count_fields
+                // This filed has been flattened so dont count it.
+                numb_o_fields -= 1;
+                // Add the flattend fields to this one, if there are any.
+                if let Some(val) = &#field_access {
+                    numb_o_fields += epee_encoding::EpeeObjectWrite::number_of_fields(val);
+                }
+            };
+        } else if is_flattened {
             count_fields = quote! {
                 #count_fields
                 // This filed has been flattened so dont count it.
                 numb_o_fields -= 1;
                 // Add the flattend fields to this one.
-                numb_o_fields += self.#field_name.number_of_fields();
+                numb_o_fields += epee_encoding::EpeeObjectWrite::number_of_fields(&#field_access);
 
             };
-        } else if let Some(try_from_into) = &try_from_into {
-            struct_fields = quote! {
-                #struct_fields
-                #field_name: (Option<#try_from_into>, bool),
-            };
-        } else {
-            struct_fields = quote! {
-                #struct_fields
-                #field_name: (Option<#field_type>, bool),
-            };
         }
 
+        // The value of this field in the builder before it is read.
+        let builder_default = match &default_val {
+            Some(default_val) => quote!(Some(#default_val)),
+            None => quote!(epee_encoding::EpeeValue::epee_default_value()),
+        };
+
         let inner_write_field = if let Some(try_from_into) = &try_from_into {
             quote! {
-                epee_encoding::write_field(&Into::<#try_from_into>::into(self.#field_name.clone()), &#epee_name, w)?;
+                epee_encoding::write_field(&Into::<#try_from_into>::into(#field_access.clone()), &#name_tokens, w)?;
+            }
+        } else if name_const.is_none() {
+            // Fixed size values are always written with their `MARKER` so write the
+            // name and marker in one go instead of going through `write_field`.
+            let name_len = epee_name.len() as u8;
+            let name_bytes = epee_name.as_bytes();
+            quote! {
+                if <#write_type as epee_encoding::EpeeValue>::FIXED_SIZE.is_some() {
+                    w.write_all(&[#name_len, #(#name_bytes,)* <#write_type as epee_encoding::EpeeValue>::MARKER.as_u8()])?;
+                    epee_encoding::EpeeValue::write(&#write_access, w)?;
+                } else {
+                    epee_encoding::write_field(&#write_access, &#name_tokens, w)?;
+                }
             }
         } else {
             quote! {
-                epee_encoding::write_field(&self.#field_name, &#epee_name, w)?;
+                epee_encoding::write_field(&#write_access, &#name_tokens, w)?;
             }
         };
 
@@ -155,7 +1390,10 @@ fn build(fields: &Fields, struct_name: &Ident) -> TokenStream {
         // this specific epee field.
         if let Some(default_val) = default_val {
             if is_flattened {
-                panic!("Cant have a default on a flattened field");
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "Cant have a default on a flattened field",
+                ));
             };
 
             default_values = quote! {
@@ -163,50 +1401,43 @@ fn build(fields: &Fields, struct_name: &Ident) -> TokenStream {
                 #field_name: (Some(#default_val), false),
             };
 
-            if try_from_into.is_some() {
-                count_fields = quote! {
-                    #count_fields
-                    if self.#field_name == #default_val.into() {
-                        numb_o_fields -= 1;
-                    };
-                };
-                write_fields = quote! {
-                    #write_fields
-                    if self.#field_name != #default_val.into() {
-                         #inner_write_field
-                    }
-                }
-            } else {
-                count_fields = quote! {
-                    #count_fields
-                    if self.#field_name == #default_val {
-                        numb_o_fields -= 1;
-                    };
+            let is_default = match (&default_if, &try_from_into) {
+                (Some(default_if), _) => quote!(#default_if(&#field_access)),
+                (None, Some(_)) => quote!(#field_access == #default_val.into()),
+                (None, None) => quote!(#field_access == #default_val),
+            };
+
+            count_fields = quote! {
+                #count_fields
+                if #is_default {
+                    numb_o_fields -= 1;
                 };
+            };
 
-                write_fields = quote! {
-                    #write_fields
-                    if self.#field_name != #default_val {
-                         #inner_write_field
-                    }
+            write_fields = quote! {
+                #write_fields
+                if !(#is_default) {
+                     #inner_write_field
                 }
             }
         } else if !is_flattened {
             if let Some(try_from_into) = &try_from_into {
                 count_fields = quote! {
                     #count_fields
-                    if !epee_encoding::EpeeValue::should_write(&Into::<#try_from_into>::into(self.#field_name.clone())) {
+                    if !epee_encoding::EpeeValue::should_write(&Into::<#try_from_into>::into(#field_access.clone())) {
                         numb_o_fields -= 1;
                     };
                 };
             } else {
                 count_fields = quote! {
                     #count_fields
-                    if !epee_encoding::EpeeValue::should_write(&self.#field_name) {
+                    if !epee_encoding::EpeeValue::should_write(&#write_access) {
                         numb_o_fields -= 1;
                     };
                 };
             }
+            // Start with the types default value so fields which are not written when
+            // empty (like sequences) don't cause a missing field error in `finish`.
             default_values = quote! {
                 #default_values
                 #field_name: (epee_encoding::EpeeValue::epee_default_value(), false),
@@ -222,10 +1453,19 @@ fn build(fields: &Fields, struct_name: &Ident) -> TokenStream {
                 #field_name: Default::default(),
             };
 
-            write_fields = quote! {
-                #write_fields
-                self.#field_name.write_fields(w)?;
-            };
+            if flattened_option.is_some() {
+                write_fields = quote! {
+                    #write_fields
+                    if let Some(val) = &#field_access {
+                        epee_encoding::EpeeObjectWrite::write_fields(val, w)?;
+                    }
+                };
+            } else {
+                write_fields = quote! {
+                    #write_fields
+                    epee_encoding::EpeeObjectWrite::write_fields(&#field_access, w)?;
+                };
+            }
         };
 
         // This is what these values do:
@@ -239,7 +1479,24 @@ fn build(fields: &Fields, struct_name: &Ident) -> TokenStream {
         //    }
         //    Ok(true)
         // }
-        if is_flattened {
+        if flattened_option.is_some() {
+            read_catch_all = quote! {
+                #read_catch_all
+                if self.#field_name.0.add_field(name, r)? {
+                    self.#field_name.1 = true;
+                    return Ok(true);
+                };
+            };
+
+            object_finish = quote! {
+                #object_finish
+                #member: if self.#field_name.1 {
+                    Some(self.#field_name.0.finish()?)
+                } else {
+                    None
+                },
+            };
+        } else if is_flattened {
             read_catch_all = quote! {
                 #read_catch_all
                 if self.#field_name.add_field(name, r)? {
@@ -249,25 +1506,92 @@ fn build(fields: &Fields, struct_name: &Ident) -> TokenStream {
 
             object_finish = quote! {
                 #object_finish
-                #field_name: self.#field_name.finish()?,
+                #member: self.#field_name.finish()?,
             };
         } else {
-            if try_from_into.is_some() {
+            // The error messages for this field.
+            let missing_field = format!("Required field `{}` was not found!", epee_name);
+            let conversion_failed =
+                format!("Error converting field `{}` using try_into", epee_name);
+
+            let reusable = try_from_into.is_none() && !is_lossy_utf8 && is_reusable(field_type);
+
+            // A reused field holds the old value until it is read, so if it was not read
+            // it is set to the value it would have had without `reuse`.
+            let field_val = if reusable {
+                reuse_fields = quote! {
+                    #reuse_fields
+                    self.#field_name.0 = Some(core::mem::take(&mut old.#member));
+                };
+                quote! {
+                    (if self.#field_name.1 {
+                        self.#field_name.0
+                    } else {
+                        #builder_default
+                    })
+                }
+            } else {
+                quote!(self.#field_name.0)
+            };
+
+            // With `all_optional` a missing field is filled with `Default::default()`
+            // instead of erroring, fields with their own default will already be `Some`.
+            if all_optional && try_from_into.is_some() {
+                object_finish = quote! {
+                    #object_finish
+                    #member: match #field_val {
+                        Some(val) => val.try_into().map_err(|_| epee_encoding::error::Error::Value(#conversion_failed))?,
+                        None => Default::default(),
+                    },
+                };
+            } else if all_optional {
                 object_finish = quote! {
                     #object_finish
-                    #field_name: self.#field_name.0.ok_or_else(|| epee_encoding::error::Error::Format("Required field was not found!"))?
-                                 .try_into().map_err(|_| epee_encoding::error::Error::Format("Error converting data using try_into"))?,
+                    #member: #field_val.unwrap_or_default(),
+                };
+            } else if try_from_into.is_some() {
+                object_finish = quote! {
+                    #object_finish
+                    #member: #field_val.ok_or_else(|| epee_encoding::error::Error::Format(#missing_field))?
+                                 .try_into().map_err(|_| epee_encoding::error::Error::Value(#conversion_failed))?,
                 };
             } else {
                 object_finish = quote! {
                     #object_finish
-                    #field_name: self.#field_name.0.ok_or_else(|| epee_encoding::error::Error::Format("Required field was not found!"))?,
+                    #member: #field_val.ok_or_else(|| epee_encoding::error::Error::Format(#missing_field))?,
                 };
             }
+            let read_value = if is_lossy_utf8 {
+                quote!(epee_encoding::read_string_lossy(r)?)
+            } else {
+                quote!(epee_encoding::read_epee_value(r)?)
+            };
+            let read_field = if reusable {
+                quote! {
+                    match &mut self.#field_name.0 {
+                        Some(val) => epee_encoding::read_epee_value_into(val, r)?,
+                        None => {
+                            self.#field_name.0.replace(#read_value);
+                        }
+                    }
+                }
+            } else {
+                quote!(self.#field_name.0.replace(#read_value);)
+            };
+            // The length of a constant name can only be checked by the compiler.
+            let check_name_const = name_const.as_ref().map(|path| {
+                quote! {
+                    const _: () = assert!(
+                        !#path.is_empty() && #path.len() <= 255,
+                        "Epee field names must be between 1 and 255 bytes long"
+                    );
+                }
+            });
             read_match_body = quote! {
                 #read_match_body
-                #epee_name => {
-                    self.#field_name.0.replace(epee_encoding::read_epee_value(r)?);
+                #name_tokens => {
+                    #check_name_const
+                    #read_field
                     if self.#field_name.1 {
                         return Err(epee_encoding::error::Error::Format("Double key in data!"))
                     }
@@ -277,69 +1601,36 @@ fn build(fields: &Fields, struct_name: &Ident) -> TokenStream {
         }
     }
 
-    let builder_name = Ident::new(&format!("__{}EpeeBuilder", struct_name), Span::call_site());
-    let mod_name = Ident::new(&format!("__{}_epee_module", struct_name), Span::call_site());
-
-    let builder_impl = quote! {
-        pub struct #builder_name {
-            #struct_fields
-        }
-
-        impl Default for #builder_name {
-            fn default() -> Self {
-                Self {
-                    #default_values
-                }
-            }
-        }
-
-        impl epee_encoding::EpeeObjectBuilder<#struct_name> for #builder_name {
-            fn add_field<R: epee_encoding::io::Read>(&mut self, name: &str, r: &mut R) -> epee_encoding::error::Result<bool> {
-                match name {
-                    #read_match_body
-                    _ => {
-                        #read_catch_all
-                        return Ok(false);
-                    }
-                };
-
-                Ok(true)
-            }
-
-            fn finish(self) -> epee_encoding::error::Result<#struct_name> {
-                Ok(#struct_name {
-                    #object_finish
-                })
-            }
-        }
-    };
-
-    let object_impl = quote! {
-        impl EpeeObject for #struct_name {
-            type Builder = #mod_name::#builder_name;
-
-            fn number_of_fields(&self) -> u64 {
-                let mut numb_o_fields: u64 = #numb_o_fields;
-                #count_fields
-                numb_o_fields
-            }
-
-
-            fn write_fields<W: epee_encoding::io::Write>(&self, w: &mut W) -> epee_encoding::error::Result<()> {
-
-                #write_fields
-
-                Ok(())
-            }
-        }
-    };
-
-    quote! {
-        mod #mod_name {
-            use super::*;
-            #builder_impl
-        }
+    if let Some(catch_all_field) = catch_all_field {
+        read_catch_all = quote! {
+            #read_catch_all
+            #catch_all_field
+        };
 
-        #object_impl
+        let known_names = quote! {
+            let __epee_known_names: &[&str] = &[#(#known_names),*];
+        };
+        count_fields = quote! {
+            #known_names
+            #count_fields
+        };
+        write_fields = quote! {
+            #known_names
+            #write_fields
+        };
     }
+
+    Ok(BuiltFields {
+        struct_fields,
+        default_values,
+        count_fields,
+        write_fields,
+        read_match_body,
+        read_catch_all,
+        object_finish,
+        reuse_fields,
+        debug_fields,
+        numb_o_fields,
+        size_hint,
+    })
 }
@@ -0,0 +1,137 @@
+/// This module contains [`Value`], a dynamically typed epee value which can hold
+/// any value found in epee data.
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::io::*;
+use crate::varint::*;
+use crate::{read_field_name, read_marker, EpeeValue, Error, InnerMarker, Result};
+
+/// Any epee value, this keeps the exact type of the value from the data so it can
+/// be written back unchanged.
+///
+/// This allows decoding data without knowing its layout, see [`from_bytes_value`](crate::from_bytes_value),
+/// and keeping fields which are not known, see `epee_catch_all`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    I64(i64),
+    I32(i32),
+    I16(i16),
+    I8(i8),
+    U64(u64),
+    U32(u32),
+    U16(u16),
+    U8(u8),
+    F64(f64),
+    /// A byte array, epee strings don't have to be valid UTF-8.
+    String(Vec<u8>),
+    Bool(bool),
+    Object(BTreeMap<String, Value>),
+    /// A sequence of values with the type of the values, every value must be of
+    /// that type and must not be a sequence.
+    Seq(InnerMarker, Vec<Value>),
+}
+
+impl Value {
+    /// Returns the value as a `u64` if it is an integer which fits in a `u64`.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Value::U64(val) => Some(val),
+            Value::U32(val) => Some(val.into()),
+            Value::U16(val) => Some(val.into()),
+            Value::U8(val) => Some(val.into()),
+            Value::I64(val) => val.try_into().ok(),
+            Value::I32(val) => val.try_into().ok(),
+            Value::I16(val) => val.try_into().ok(),
+            Value::I8(val) => val.try_into().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an `i64` if it is an integer which fits in an `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Value::I64(val) => Some(val),
+            Value::I32(val) => Some(val.into()),
+            Value::I16(val) => Some(val.into()),
+            Value::I8(val) => Some(val.into()),
+            Value::U64(val) => val.try_into().ok(),
+            Value::U32(val) => Some(val.into()),
+            Value::U16(val) => Some(val.into()),
+            Value::U8(val) => Some(val.into()),
+            _ => None,
+        }
+    }
+
+    /// Returns the value if it is an `F64`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::F64(val) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Returns the value if it is a `Bool`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Value::Bool(val) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes of a `String` value.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::String(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Returns a `String` value as a `&str`, if it is valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        self.as_bytes()
+            .and_then(|bytes| core::str::from_utf8(bytes).ok())
+    }
+
+    /// Returns the fields of an `Object` value.
+    pub fn as_object(&self) -> Option<&BTreeMap<String, Value>> {
+        match self {
+            Value::Object(fields) => Some(fields),
+            _ => None,
+        }
+    }
+
+    /// Returns the values of a `Seq` value.
+    pub fn as_seq(&self) -> Option<&[Value]> {
+        match self {
+            Value::Seq(_, values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the field `key` of an `Object` value, `None` is returned if the value is
+    /// not an object or the field is not in it.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_object().and_then(|fields| fields.get(key))
+    }
+}
+
+/// Read the fields of an object into a map.
+pub(crate) fn read_value_object<R: Read>(r: &mut R) -> Result<BTreeMap<String, Value>> {
+    let number_o_field = read_varint_bounded(r, r.decode_options().max_fields)?;
+    r.add_fields(number_o_field);
+
+    let mut fields = BTreeMap::new();
+    let mut name_buf = [0; 255];
+    for _ in 0..number_o_field {
+        let field_name = read_field_name(r, &mut name_buf)?.into();
+        let marker = read_marker(r)?;
+        let val = Value::read(r, &marker)?;
+
+        if fields.insert(field_name, val).is_some() {
+            return Err(Error::Format("Double key in data!"));
+        }
+    }
+    Ok(fields)
+}
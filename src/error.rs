@@ -1,44 +1,120 @@
-use core::fmt::{Debug, Formatter};
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt::{Debug, Display, Formatter};
 use core::num::TryFromIntError;
 
+use crate::Marker;
+
 pub type Result<T> = core::result::Result<T, Error>;
 
-#[cfg_attr(feature = "std", derive(thiserror::Error))]
 pub enum Error {
-    #[cfg_attr(feature = "std", error("IO error: {0}"))]
     IO(&'static str),
-    #[cfg_attr(feature = "std", error("Format error: {0}"))]
     Format(&'static str),
-    #[cfg_attr(feature = "std", error("Value error: {0}"))]
     Value(&'static str),
+    /// A value's marker was not the marker of the type being read.
+    UnexpectedMarker {
+        expected: Marker,
+        found: Marker,
+    },
+    /// A fixed length array had a different length to the one expected.
+    LengthMismatch {
+        expected: u64,
+        found: u64,
+    },
+    /// A length read from the data does not fit in a `usize` on this platform, this can
+    /// only happen on platforms where `usize` is smaller than 64 bits.
+    LengthTooLarge {
+        len: u64,
+    },
+    /// The data did not start with the epee header, `found` is the bytes read instead.
+    HeaderMismatch {
+        found: [u8; 9],
+    },
+    /// An error which happened while reading the field `name` of an object.
+    Field {
+        name: String,
+        error: Box<Error>,
+    },
 }
 
 impl Error {
-    fn field_name(&self) -> &'static str {
+    /// Returns the error without the context of which field it happened in.
+    pub fn root(&self) -> &Error {
         match self {
-            Error::IO(_) => "io",
-            Error::Format(_) => "format",
-            Error::Value(_) => "value",
+            Error::Field { error, .. } => error.root(),
+            _ => self,
         }
     }
+}
 
-    fn field_data(&self) -> &'static str {
+impl Debug for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut debug = f.debug_struct("Error");
         match self {
-            Error::IO(data) => data,
-            Error::Format(data) => data,
-            Error::Value(data) => data,
-        }
+            Error::IO(data) => debug.field("io", data),
+            Error::Format(data) => debug.field("format", data),
+            Error::Value(data) => debug.field("value", data),
+            Error::UnexpectedMarker { expected, found } => debug
+                .field("expected_marker", expected)
+                .field("found_marker", found),
+            Error::LengthMismatch { expected, found } => debug
+                .field("expected_len", expected)
+                .field("found_len", found),
+            Error::LengthTooLarge { len } => debug.field("len", len),
+            Error::HeaderMismatch { found } => debug.field("found_header", found),
+            Error::Field { name, error } => debug.field("field", name).field("error", error),
+        };
+        debug.finish()
     }
 }
 
-impl Debug for Error {
+impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("Error")
-            .field(self.field_name(), &self.field_data())
-            .finish()
+        match self {
+            Error::IO(data) => write!(f, "IO error: {}", data),
+            Error::Format(data) => write!(f, "Format error: {}", data),
+            Error::Value(data) => write!(f, "Value error: {}", data),
+            Error::UnexpectedMarker { expected, found } => write!(
+                f,
+                "Format error: Expected marker `{}` but found `{}`",
+                expected, found
+            ),
+            Error::LengthMismatch { expected, found } => write!(
+                f,
+                "Format error: Expected array of length {} but found length {}",
+                expected, found
+            ),
+            Error::LengthTooLarge { len } => write!(
+                f,
+                "Format error: Length {} does not fit in a usize on this platform",
+                len
+            ),
+            Error::HeaderMismatch { found } => {
+                write!(f, "Format error: Expected header `")?;
+                write_hex(f, crate::HEADER)?;
+                write!(f, "` but found `")?;
+                write_hex(f, found)?;
+                write!(f, "`")
+            }
+            Error::Field { name, error } => write!(f, "Error in field `{}`: {}", name, error),
+        }
+    }
+}
+
+/// Writes `bytes` as space separated hex.
+fn write_hex(f: &mut Formatter<'_>, bytes: &[u8]) -> core::fmt::Result {
+    for (i, byte) in bytes.iter().enumerate() {
+        if i != 0 {
+            write!(f, " ")?;
+        }
+        write!(f, "{:02x}", byte)?;
     }
+    Ok(())
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 impl From<TryFromIntError> for Error {
     fn from(_: TryFromIntError) -> Self {
         Error::Value("Int is too large")
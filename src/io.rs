@@ -3,10 +3,9 @@
 /// This was taken from std-shims which is licensed under MIT and
 /// Copyright (c) 2023 Luke Parker.
 use alloc::string::String;
-use alloc::vec;
 use alloc::vec::Vec;
 
-use crate::{Error, Result};
+use crate::{DecodeOptions, DecodeStats, Error, Result, MAX_PREALLOCATED_BYTES};
 
 pub trait Read {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
@@ -18,6 +17,38 @@ pub trait Read {
         }
         Ok(())
     }
+
+    /// Returns the state used to enforce the limits in [`DecodeOptions`], only the reader
+    /// this crate wraps data in when decoding returns `Some`. The returned type can't be
+    /// named outside of this crate so this can't be overridden.
+    #[doc(hidden)]
+    fn limits(&self) -> Option<&DecodeLimits> {
+        None
+    }
+
+    /// Like [`Read::limits`] but returns a mutable reference.
+    #[doc(hidden)]
+    fn limits_mut(&mut self) -> Option<&mut DecodeLimits> {
+        None
+    }
+}
+
+impl<R: Read + ?Sized> Read for &mut R {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (**self).read(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        (**self).read_exact(buf)
+    }
+
+    fn limits(&self) -> Option<&DecodeLimits> {
+        (**self).limits()
+    }
+
+    fn limits_mut(&mut self) -> Option<&mut DecodeLimits> {
+        (**self).limits_mut()
+    }
 }
 
 impl Read for &[u8] {
@@ -32,6 +63,314 @@ impl Read for &[u8] {
     }
 }
 
+/// An adapter to use a [`std::io::Read`] as a [`Read`], this allows decoding
+/// directly from a socket or file without reading it into a buffer first.
+///
+/// Unlike [`Read::read`] on a byte slice [`std::io::Read::read`] can return less
+/// bytes than requested before the end of the data, so [`Read::read_exact`] uses
+/// [`std::io::Read::read_exact`] which keeps reading until the buffer is full.
+#[cfg(feature = "std")]
+pub struct IoReader<R>(R);
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> IoReader<R> {
+    pub fn new(inner: R) -> Self {
+        IoReader(inner)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Read for IoReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.0
+            .read(buf)
+            .map_err(|_| Error::IO("Error reading from std::io::Read"))
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.0.read_exact(buf).map_err(|e| match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => Error::IO("Reader ran out of bytes"),
+            _ => Error::IO("Error reading from std::io::Read"),
+        })
+    }
+}
+
+mod limits {
+    use crate::{DecodeOptions, DecodeStats, Error, Result};
+
+    /// The limits in [`DecodeOptions`] and what has been read so far, this is `pub` in a
+    /// private module so it can be returned by [`Read::limits`](super::Read::limits)
+    /// without being usable outside of this crate.
+    pub struct DecodeLimits {
+        pub(crate) options: DecodeOptions,
+        depth: u8,
+        max_depth: u8,
+        objects: u64,
+        skipped_objects: u64,
+        fields: u64,
+        seq_elements: u64,
+    }
+
+    impl DecodeLimits {
+        pub(crate) fn new(options: DecodeOptions) -> Self {
+            DecodeLimits {
+                options,
+                depth: 0,
+                max_depth: 0,
+                objects: 0,
+                skipped_objects: 0,
+                fields: 0,
+                seq_elements: 0,
+            }
+        }
+
+        pub(crate) fn stats(&self) -> DecodeStats {
+            DecodeStats {
+                objects: self.objects,
+                skipped_objects: self.skipped_objects,
+                fields: self.fields,
+                seq_elements: self.seq_elements,
+                max_depth: self.max_depth,
+            }
+        }
+
+        pub(crate) fn enter_object(&mut self) -> Result<()> {
+            self.objects += 1;
+            if self.objects > self.options.max_objects {
+                return Err(Error::Format("Number of objects exceeded maximum"));
+            }
+
+            // Checked before incrementing so a maximum of `u8::MAX` can't overflow the depth.
+            if self.depth >= self.options.max_object_depth {
+                return Err(Error::Format("Depth of objects exceeded maximum"));
+            }
+            self.depth += 1;
+            self.max_depth = self.max_depth.max(self.depth);
+            Ok(())
+        }
+
+        pub(crate) fn exit_object(&mut self) {
+            self.depth -= 1;
+        }
+
+        pub(crate) fn add_seq_elements(&mut self, len: u64) -> Result<()> {
+            self.seq_elements = self.seq_elements.saturating_add(len);
+            if self.seq_elements > self.options.max_seq_elements {
+                return Err(Error::Format(
+                    "Number of sequence elements exceeded maximum",
+                ));
+            }
+            Ok(())
+        }
+
+        pub(crate) fn add_fields(&mut self, len: u64) {
+            self.fields = self.fields.saturating_add(len);
+        }
+
+        pub(crate) fn skip_object(&mut self) {
+            self.skipped_objects += 1;
+        }
+    }
+}
+
+pub(crate) use limits::DecodeLimits;
+
+/// The decoder's bookkeeping, these do nothing for readers without [`DecodeLimits`].
+///
+/// Nested objects must be read with a reader that [`is_limited`](ReadLimits::is_limited)
+/// so the depth of recursive types is always limited, see [`limited`].
+pub(crate) trait ReadLimits: Read {
+    /// Returns true if this reader enforces the limits in [`DecodeOptions`].
+    fn is_limited(&self) -> bool {
+        self.limits().is_some()
+    }
+
+    /// Called before a nested object is read, errors if the maximum depth or number
+    /// of objects has been exceeded.
+    fn enter_object(&mut self) -> Result<()> {
+        match self.limits_mut() {
+            Some(limits) => limits.enter_object(),
+            None => Ok(()),
+        }
+    }
+
+    /// Called after a nested object has been read.
+    fn exit_object(&mut self) {
+        if let Some(limits) = self.limits_mut() {
+            limits.exit_object();
+        }
+    }
+
+    /// Called before the `len` elements of a sequence are read, errors if the maximum
+    /// number of elements has been exceeded.
+    fn add_seq_elements(&mut self, len: u64) -> Result<()> {
+        match self.limits_mut() {
+            Some(limits) => limits.add_seq_elements(len),
+            None => Ok(()),
+        }
+    }
+
+    /// Called before the `len` fields of an object are read.
+    fn add_fields(&mut self, len: u64) {
+        if let Some(limits) = self.limits_mut() {
+            limits.add_fields(len);
+        }
+    }
+
+    /// Called before an object which is not needed is skipped.
+    fn skip_object(&mut self) {
+        if let Some(limits) = self.limits_mut() {
+            limits.skip_object();
+        }
+    }
+
+    /// Returns the limits to use when decoding from this reader.
+    fn decode_options(&self) -> &DecodeOptions {
+        match self.limits() {
+            Some(limits) => &limits.options,
+            None => &DecodeOptions::DEFAULT,
+        }
+    }
+}
+
+impl<R: Read + ?Sized> ReadLimits for R {}
+
+/// Wraps a reader which is not [`limited`](ReadLimits::is_limited) so it enforces the
+/// default limits.
+///
+/// The reader is wrapped as a `dyn Read` so reading with the wrapped reader, which may
+/// call this again, uses the same reader type and doesn't recurse forever when generic
+/// functions are instantiated.
+pub(crate) fn limited<R: Read>(r: &mut R) -> LimitedReader<&mut dyn Read> {
+    LimitedReader::new(r as &mut dyn Read, DecodeOptions::DEFAULT)
+}
+
+/// A [`Read`] wrapper which enforces the limits in [`DecodeOptions`], this stops
+/// recursive types from overflowing the stack on malicious data.
+pub(crate) struct LimitedReader<R> {
+    inner: R,
+    limits: DecodeLimits,
+}
+
+impl<R: Read> LimitedReader<R> {
+    pub(crate) fn new(inner: R, options: DecodeOptions) -> Self {
+        LimitedReader {
+            inner,
+            limits: DecodeLimits::new(options),
+        }
+    }
+
+    /// Returns the statistics of what has been read so far.
+    pub(crate) fn stats(&self) -> DecodeStats {
+        self.limits.stats()
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub(crate) fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.inner.read_exact(buf)
+    }
+
+    fn limits(&self) -> Option<&DecodeLimits> {
+        Some(&self.limits)
+    }
+
+    fn limits_mut(&mut self) -> Option<&mut DecodeLimits> {
+        Some(&mut self.limits)
+    }
+}
+
+/// A [`Read`] which keeps a copy of the bytes read from `inner`, the limits of `inner`
+/// are used.
+pub(crate) struct RecordingReader<'a, R: ?Sized> {
+    inner: &'a mut R,
+    bytes: Vec<u8>,
+}
+
+impl<'a, R: Read + ?Sized> RecordingReader<'a, R> {
+    pub(crate) fn new(inner: &'a mut R) -> Self {
+        RecordingReader {
+            inner,
+            bytes: Vec::new(),
+        }
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl<'a, R: Read + ?Sized> Read for RecordingReader<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.bytes.extend_from_slice(&buf[..read]);
+        Ok(read)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.inner.read_exact(buf)?;
+        self.bytes.extend_from_slice(buf);
+        Ok(())
+    }
+
+    fn limits(&self) -> Option<&DecodeLimits> {
+        self.inner.limits()
+    }
+
+    fn limits_mut(&mut self) -> Option<&mut DecodeLimits> {
+        self.inner.limits_mut()
+    }
+}
+
+/// A [`Read`] which reads bytes kept by a [`RecordingReader`] with the limits of another
+/// reader, so the bytes are read again as if they were read from that reader.
+///
+/// This isn't generic over the other reader so reading a value with it, which may need
+/// another [`ReplayReader`], doesn't need an endless number of reader types.
+pub(crate) struct ReplayReader<'a> {
+    bytes: &'a [u8],
+    limits: Option<&'a mut DecodeLimits>,
+}
+
+impl<'a> ReplayReader<'a> {
+    pub(crate) fn new<R: Read + ?Sized>(bytes: &'a [u8], limits_of: &'a mut R) -> Self {
+        ReplayReader {
+            bytes,
+            limits: limits_of.limits_mut(),
+        }
+    }
+}
+
+impl<'a> Read for ReplayReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.bytes.read(buf)
+    }
+
+    fn limits(&self) -> Option<&DecodeLimits> {
+        self.limits.as_deref()
+    }
+
+    fn limits_mut(&mut self) -> Option<&mut DecodeLimits> {
+        self.limits.as_deref_mut()
+    }
+}
+
 pub trait Write {
     fn write(&mut self, buf: &[u8]) -> Result<usize>;
     fn write_all(&mut self, buf: &[u8]) -> Result<()> {
@@ -49,18 +388,85 @@ impl Write for Vec<u8> {
     }
 }
 
+/// A [`Write`] which only counts the bytes written to it, this is used by
+/// [`encoded_len`](crate::encoded_len) to get the length of an object without allocating.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ByteCounter(usize);
+
+impl ByteCounter {
+    pub fn new() -> Self {
+        ByteCounter(0)
+    }
+
+    /// Returns the number of bytes written.
+    pub fn count(&self) -> usize {
+        self.0
+    }
+}
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+}
+
+/// An adapter to use a [`std::io::Write`] as a [`Write`], this allows encoding
+/// directly into a socket or file without allocating a buffer first.
+#[cfg(feature = "std")]
+pub struct IoWriter<W>(W);
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> IoWriter<W> {
+    pub fn new(inner: W) -> Self {
+        IoWriter(inner)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Write for IoWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.0
+            .write(buf)
+            .map_err(|_| Error::IO("Error writing to std::io::Write"))
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        self.0
+            .write_all(buf)
+            .map_err(|_| Error::IO("Error writing to std::io::Write"))
+    }
+}
+
 pub(crate) fn read_bytes<R: Read, const N: usize>(r: &mut R) -> Result<[u8; N]> {
     let mut res = [0; N];
     r.read_exact(&mut res)?;
     Ok(res)
 }
 
+/// Reads `len` bytes, the bytes are read in chunks so a large `len` does not
+/// allocate until the bytes have actually been read.
 pub(crate) fn read_var_bytes<R: Read>(r: &mut R, len: usize) -> Result<Vec<u8>> {
-    let mut res = vec![0; len];
-    r.read_exact(&mut res)?;
+    let mut res = Vec::new();
+    read_var_bytes_into(r, len, &mut res)?;
     Ok(res)
 }
 
+/// Like [`read_var_bytes`] but reads into `buf`, reusing its allocation.
+pub(crate) fn read_var_bytes_into<R: Read>(r: &mut R, len: usize, buf: &mut Vec<u8>) -> Result<()> {
+    buf.clear();
+    while buf.len() < len {
+        let start = buf.len();
+        buf.resize(start + (len - start).min(MAX_PREALLOCATED_BYTES), 0);
+        r.read_exact(&mut buf[start..])?;
+    }
+    Ok(())
+}
+
 pub(crate) fn read_byte<R: Read>(r: &mut R) -> Result<u8> {
     Ok(read_bytes::<_, 1>(r)?[0])
 }
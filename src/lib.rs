@@ -6,7 +6,7 @@
 //!
 //! example without derive:
 //! ```rust
-//! use epee_encoding::{EpeeObject, EpeeObjectBuilder, read_epee_value, write_field, to_bytes, from_bytes};
+//! use epee_encoding::{EpeeObject, EpeeObjectBuilder, EpeeObjectWrite, read_epee_value, write_field, to_bytes, from_bytes};
 //! use epee_encoding::io::{Read, Write};
 //!
 //! pub struct Test {
@@ -38,7 +38,9 @@
 //!
 //! impl EpeeObject for Test {
 //!     type Builder = __TestEpeeBuilder;
+//! }
 //!
+//! impl EpeeObjectWrite for Test {
 //!     fn number_of_fields(&self) -> u64 {
 //!         1
 //!     }
@@ -75,24 +77,49 @@
 
 extern crate alloc;
 
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::vec::Vec;
 
+mod dynamic;
 pub mod error;
 pub mod io;
 pub mod marker;
+#[cfg(feature = "std")]
+mod net;
+mod options;
+mod pending;
+mod stats;
+mod tuple;
 mod value;
-mod varint;
+pub mod varint;
 
 #[cfg(feature = "derive")]
 pub use epee_encoding_derive::EpeeObject;
 
+pub use dynamic::Value;
 pub use error::*;
 use io::*;
 pub use marker::{InnerMarker, Marker};
-pub use value::EpeeValue;
+pub use options::DecodeOptions;
+pub use stats::DecodeStats;
+pub use value::{EpeeSeqItem, EpeeValue, EpeeValueDyn, PackedBools, RawU8Seq};
 use varint::*;
 
+/// Items used by the derive macro, these are not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    /// The seal of [`EpeeValue`](crate::EpeeValue), used to implement it for newtypes.
+    pub use crate::value::__seal_epee_value::Sealed as EpeeValueSeal;
+
+    /// Reference fields of write only objects are written as a `Borrowed`.
+    pub use crate::value::Borrowed;
+
+    /// Fields of enums read before the tag.
+    pub use crate::pending::PendingFields;
+}
+
 /// Header that needs to be at the beginning of every binary blob that follows
 /// this binary serialization format.
 const HEADER: &[u8] = b"\x01\x11\x01\x01\x01\x01\x02\x01\x01";
@@ -100,8 +127,13 @@ const HEADER: &[u8] = b"\x01\x11\x01\x01\x01\x01\x02\x01\x01";
 const MAX_STRING_LEN_POSSIBLE: u64 = 2000000000;
 /// The maximum depth of skipped objects.
 const MAX_DEPTH_OF_SKIPPED_OBJECTS: u8 = 20;
+/// The maximum depth of nested objects.
+const MAX_OBJECT_DEPTH: u8 = 100;
 /// The maximum number of fields in an object.
 const MAX_NUM_FIELDS: u64 = 1000;
+/// The maximum number of bytes allocated up front for a sequence or byte array,
+/// lengths are read from the data so larger values are grown as they are read.
+const MAX_PREALLOCATED_BYTES: usize = 64 * 1024;
 
 /// A trait for an object that can build a type `T` from the epee format.
 pub trait EpeeObjectBuilder<T>: Default + Sized {
@@ -114,36 +146,276 @@ pub trait EpeeObjectBuilder<T>: Default + Sized {
 
     /// Called when the number of fields has been read.
     fn finish(self) -> Result<T>;
-}
 
-/// A trait for an object that can be turned into epee bytes.
-pub trait EpeeObject: Sized {
-    type Builder: EpeeObjectBuilder<Self>;
+    /// Called before any fields are added with a value whose allocations can be
+    /// reused, see [`from_bytes_into`]. Values taken from `old` must not end up in
+    /// the built object unless their field is read.
+    ///
+    /// Defaults to doing nothing.
+    fn reuse(&mut self, old: &mut T) {
+        let _ = old;
+    }
 
-    /// Returns the number of fields to be encoded.
+    /// Resets the builder to the state it was in before any fields were added, so it
+    /// can be used again after an error.
+    ///
+    /// Defaults to replacing the builder with [`Default::default`], manual builders
+    /// holding allocations can override this to keep them.
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// A trait for an object that can be turned into epee bytes, this is the write half of
+/// [`EpeeObject`].
+///
+/// Objects which can only be written, like derived structs with `&str` or `&[u8]`
+/// fields, only implement this trait so they can't be decoded.
+pub trait EpeeObjectWrite {
+    /// Returns the number of fields to be encoded, this must match the number of
+    /// fields written by [`EpeeObjectWrite::write_fields`].
     fn number_of_fields(&self) -> u64;
 
     /// write the objects fields into the writer.
     fn write_fields<W: Write>(&self, w: &mut W) -> Result<()>;
+
+    /// Write the object, the number of fields followed by the fields, into the writer.
+    ///
+    /// By default this writes [`EpeeObjectWrite::number_of_fields`] then calls
+    /// [`EpeeObjectWrite::write_fields`]. This can be overridden when the number of fields
+    /// is only known by writing them, for example with [`write_object_streaming`].
+    ///
+    /// When this object is flattened into another `number_of_fields` and `write_fields`
+    /// are used directly, so they should still agree.
+    fn write_object<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_varint(self.number_of_fields(), w)?;
+        self.write_fields(w)
+    }
+
+    /// Returns an estimate of the number of bytes [`EpeeObjectWrite::write_fields`] will write,
+    /// this is used to reserve space when encoding. Defaults to 0.
+    fn size_hint(&self) -> usize {
+        0
+    }
+}
+
+/// A trait for an object that can be turned into and built from epee bytes.
+pub trait EpeeObject: EpeeObjectWrite + Sized {
+    type Builder: EpeeObjectBuilder<Self>;
+}
+
+/// Read the object `T` from a byte array, the array must only contain the object
+/// and an error is returned if there are bytes after the object.
+///
+/// To ignore bytes after the object use [`from_bytes_allow_trailing`].
+pub fn from_bytes<T: EpeeObject>(buf: &[u8]) -> Result<T> {
+    let (val, remaining) = from_bytes_with_remaining(buf)?;
+    check_no_trailing(remaining)?;
+    Ok(val)
+}
+
+/// Returns an error if there are `remaining` bytes after the decoded data, every
+/// function decoding a whole byte array, apart from [`from_bytes_allow_trailing`]
+/// and [`from_bytes_with_remaining`], uses this.
+fn check_no_trailing(remaining: &[u8]) -> Result<()> {
+    if !remaining.is_empty() {
+        return Err(Error::Format("Data has trailing bytes"));
+    }
+    Ok(())
+}
+
+/// Read the object `T` from a byte array, ignoring any bytes after the object.
+pub fn from_bytes_allow_trailing<T: EpeeObject>(buf: &[u8]) -> Result<T> {
+    from_bytes_with_remaining(buf).map(|(val, _)| val)
 }
 
-/// Read the object `T` from a byte array.
-pub fn from_bytes<T: EpeeObject>(mut buf: &[u8]) -> Result<T> {
-    read_head_object(&mut buf)
+/// Read the object `T` from a byte array, returning the object and the bytes
+/// after the object which were not read.
+pub fn from_bytes_with_remaining<T: EpeeObject>(buf: &[u8]) -> Result<(T, &[u8])> {
+    let mut r = LimitedReader::new(buf, DecodeOptions::DEFAULT);
+    let val = read_head_object(&mut r)?;
+    Ok((val, r.into_inner()))
+}
+
+/// Read the object `T` from a byte array, returning the object and statistics about
+/// the data decoded. The array must only contain the object.
+pub fn from_bytes_with_stats<T: EpeeObject>(buf: &[u8]) -> Result<(T, DecodeStats)> {
+    let mut r = LimitedReader::new(buf, DecodeOptions::DEFAULT);
+    let val = read_head_object(&mut r)?;
+    let stats = r.stats();
+    check_no_trailing(r.into_inner())?;
+    Ok((val, stats))
+}
+
+/// Read the object `T` from a byte array into `target`, the array must only contain
+/// the object.
+///
+/// The object's builder is given `target` first so allocations can be reused, for derived
+/// objects this is the buffers of `Vec` and `String` fields. Other fields, and
+/// the fields of nested objects, are decoded as new values. If an error is returned
+/// `target` may have had values taken from it.
+pub fn from_bytes_into<T: EpeeObject>(buf: &[u8], target: &mut T) -> Result<()> {
+    let mut r = LimitedReader::new(buf, DecodeOptions::DEFAULT);
+    read_header(&mut r)?;
+
+    let mut object_builder = T::Builder::default();
+    object_builder.reuse(target);
+
+    let mut skipped_objects = 0;
+    *target = read_object_with(&mut r, object_builder, |_, r| {
+        skip_epee_value_counted(r, &mut skipped_objects)
+    })?;
+
+    check_no_trailing(r.into_inner())
+}
+
+/// Read the object `T` from a byte array which does not start with the epee header,
+/// for objects embedded in other data. The array must only contain the object.
+pub fn from_bytes_no_header<T: EpeeObject>(buf: &[u8]) -> Result<T> {
+    let mut r = LimitedReader::new(buf, DecodeOptions::DEFAULT);
+    let val = read_object(&mut r, &mut 0)?;
+    check_no_trailing(r.into_inner())?;
+    Ok(val)
+}
+
+/// Read an object from a byte array without knowing its layout, the returned value
+/// is always a [`Value::Object`]. The array must only contain the object.
+pub fn from_bytes_value(buf: &[u8]) -> Result<Value> {
+    let mut r = LimitedReader::new(buf, DecodeOptions::DEFAULT);
+    read_header(&mut r)?;
+    let val = dynamic::read_value_object(&mut r)?;
+    check_no_trailing(r.into_inner())?;
+    Ok(Value::Object(val))
+}
+
+/// Read the object `T` from a byte array using the limits in `options`, the array must
+/// only contain the object.
+pub fn from_bytes_with_options<T: EpeeObject>(buf: &[u8], options: &DecodeOptions) -> Result<T> {
+    let mut r = LimitedReader::new(buf, options.clone());
+    let val = read_head_object(&mut r)?;
+    check_no_trailing(r.into_inner())?;
+    Ok(val)
+}
+
+/// Read the object `T` from a [`std::io::Read`], only the bytes of the object
+/// are read from the reader.
+///
+/// Pass the reader by `&mut` to keep using it after the object, a [`std::io::Cursor`]
+/// is left positioned at the first byte after the object.
+#[cfg(feature = "std")]
+pub fn from_reader<T: EpeeObject, R: std::io::Read>(r: R) -> Result<T> {
+    read_head_object(&mut LimitedReader::new(
+        IoReader::new(r),
+        DecodeOptions::DEFAULT,
+    ))
+}
+
+/// A custom reader for a field of the top level object, called with the bytes starting at
+/// the fields value (the marker). The handler must read the whole value.
+///
+/// The handler is given the original buffer (with lifetime `'a`) so values can be borrowed
+/// from it, see [`read_epee_bytes_borrowed`]. `'b` is the lifetime of the handler itself.
+pub type FieldHandler<'a, 'b> = Box<dyn FnMut(&mut &'a [u8]) -> Result<()> + 'b>;
+
+/// Read the object `T` from a byte array, fields of the top level object which `T` does not
+/// need are passed to the matching handler in `handlers` instead of being skipped.
+///
+/// Fields not needed by `T` and without a handler are skipped as normal. The array must
+/// only contain the object.
+pub fn from_bytes_with_handlers<'a, T: EpeeObject>(
+    buf: &'a [u8],
+    handlers: &mut BTreeMap<&str, FieldHandler<'a, '_>>,
+) -> Result<T> {
+    let mut r = LimitedReader::new(buf, DecodeOptions::DEFAULT);
+    read_header(&mut r)?;
+
+    let mut skipped_objects = 0;
+    let val = read_object_with(&mut r, T::Builder::default(), |name, r| {
+        match handlers.get_mut(name) {
+            Some(handler) => handler(r.inner_mut()),
+            None => skip_epee_value_counted(r, &mut skipped_objects),
+        }
+    })?;
+    check_no_trailing(r.into_inner())?;
+    Ok(val)
 }
 
 /// Turn the object into epee bytes.
-pub fn to_bytes<T: EpeeObject>(val: &T) -> Result<Vec<u8>> {
-    let mut buf = Vec::<u8>::new();
-    write_head_object(val, &mut buf)?;
+pub fn to_bytes<T: EpeeObjectWrite>(val: &T) -> Result<Vec<u8>> {
+    let mut buf = Vec::<u8>::with_capacity(
+        // The number of fields is usually 1 byte.
+        HEADER.len() + 1 + val.size_hint(),
+    );
+    to_bytes_in(val, &mut buf)?;
+    Ok(buf)
+}
+
+/// Turn any epee value into epee bytes, the value is written after the header with its
+/// marker.
+///
+/// Epee data normally has an object straight after the header with no marker, which is
+/// what [`to_bytes`] writes, monerod can't decode data written with this function. This
+/// is for formats that have a bare value, like a sequence, as the root.
+pub fn to_bytes_epee_value<T: EpeeValue>(val: &T) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    write_header(&mut buf)?;
+    write_epee_value(val, &mut buf)?;
+    Ok(buf)
+}
+
+/// Read any epee value from a byte array written by [`to_bytes_epee_value`], the header
+/// must be followed by the value's marker. The array must only contain the value.
+pub fn from_bytes_epee_value<T: EpeeValue>(buf: &[u8]) -> Result<T> {
+    let mut r = LimitedReader::new(buf, DecodeOptions::DEFAULT);
+    read_header(&mut r)?;
+    let val = read_epee_value(&mut r)?;
+    check_no_trailing(r.into_inner())?;
+    Ok(val)
+}
+
+/// Turn the object into epee bytes without the epee header, for objects embedded
+/// in other data.
+pub fn to_bytes_no_header<T: EpeeObjectWrite>(val: &T) -> Result<Vec<u8>> {
+    let mut buf = Vec::<u8>::with_capacity(1 + val.size_hint());
+    val.write_object(&mut buf)?;
     Ok(buf)
 }
 
+/// Write the object as epee bytes into the writer, this allows a buffer to be
+/// reused between calls.
+pub fn to_bytes_in<T: EpeeObjectWrite, W: Write>(val: &T, w: &mut W) -> Result<()> {
+    write_head_object(val, w)
+}
+
+/// Returns the number of bytes the object is encoded with by [`to_bytes`], the object is
+/// written to a [`io::ByteCounter`] so the bytes are not allocated.
+///
+/// This encodes the object so it costs about the same as [`to_bytes_in`] with a reused buffer.
+pub fn encoded_len<T: EpeeObjectWrite>(val: &T) -> Result<usize> {
+    let mut counter = ByteCounter::new();
+    write_head_object(val, &mut counter)?;
+    Ok(counter.count())
+}
+
+/// Write the object as epee bytes into a [`std::io::Write`].
+///
+/// Small writes are made for each value so `w` should be buffered, e.g. with a
+/// [`std::io::BufWriter`], if it is a socket or file. `w` is flushed after the
+/// object has been written.
+#[cfg(feature = "std")]
+pub fn to_writer<T: EpeeObjectWrite, W: std::io::Write>(val: &T, w: W) -> Result<()> {
+    let mut w = IoWriter::new(w);
+    write_head_object(val, &mut w)?;
+    w.into_inner()
+        .flush()
+        .map_err(|_| Error::IO("Error writing to std::io::Write"))
+}
+
 fn read_header<R: Read>(r: &mut R) -> Result<()> {
     let mut buf = [0; 9];
     r.read_exact(&mut buf)?;
     if buf != HEADER {
-        return Err(Error::Format("Data does not contain header"));
+        return Err(Error::HeaderMismatch { found: buf });
     }
     Ok(())
 }
@@ -152,9 +424,9 @@ fn write_header<W: Write>(w: &mut W) -> Result<()> {
     w.write_all(HEADER)
 }
 
-fn write_head_object<T: EpeeObject, W: Write>(val: &T, w: &mut W) -> Result<()> {
+fn write_head_object<T: EpeeObjectWrite, W: Write>(val: &T, w: &mut W) -> Result<()> {
     write_header(w)?;
-    val.write(w)
+    val.write_object(w)
 }
 
 fn read_head_object<T: EpeeObject, R: Read>(r: &mut R) -> Result<T> {
@@ -163,18 +435,52 @@ fn read_head_object<T: EpeeObject, R: Read>(r: &mut R) -> Result<T> {
     read_object(r, &mut skipped_objects)
 }
 
-fn read_field_name<R: Read>(r: &mut R) -> Result<String> {
+/// Reads the bytes of a field name into `buf`, field names are at most 255 bytes so
+/// this avoids an allocation for every field read.
+fn read_field_name_into<'a, R: Read>(r: &mut R, buf: &'a mut [u8; 255]) -> Result<&'a [u8]> {
     let len = read_byte(r)?;
-    read_string(r, len.into())
+    if len == 0 {
+        return Err(Error::Format("Field name is empty"));
+    }
+    let name = &mut buf[..len.into()];
+    r.read_exact(name)?;
+    Ok(name)
+}
+
+/// Reads a field name into `buf`, returning an error if it is not valid UTF-8.
+fn read_field_name<'a, R: Read>(r: &mut R, buf: &'a mut [u8; 255]) -> Result<&'a str> {
+    core::str::from_utf8(read_field_name_into(r, buf)?)
+        .map_err(|_| Error::Format("Field name is not valid UTF-8"))
 }
 
-fn write_field_name<W: Write>(val: &str, w: &mut W) -> Result<()> {
-    w.write(&[val.len().try_into()?])?;
-    w.write_all(val.as_bytes())
+/// Read a field name without checking it is valid UTF-8, this is for reading objects
+/// field by field when names may not be UTF-8. The field's value follows the name and
+/// can be read with [`read_epee_value`] or skipped with [`skip_epee_value`].
+pub fn read_field_name_bytes<R: Read>(r: &mut R) -> Result<Vec<u8>> {
+    let mut buf = [0; 255];
+    Ok(read_field_name_into(r, &mut buf)?.to_vec())
+}
+
+fn write_field_name<W: Write>(val: &[u8], w: &mut W) -> Result<()> {
+    if val.is_empty() {
+        return Err(Error::Value("Field name is empty"));
+    }
+    w.write_all(&[val.len().try_into()?])?;
+    w.write_all(val)
 }
 
 /// Write an epee field.
 pub fn write_field<T: EpeeValue, W: Write>(val: &T, field_name: &str, w: &mut W) -> Result<()> {
+    write_field_bytes(val, field_name.as_bytes(), w)
+}
+
+/// Write an epee field with a name which may not be valid UTF-8, otherwise the same
+/// as [`write_field`].
+pub fn write_field_bytes<T: EpeeValue, W: Write>(
+    val: &T,
+    field_name: &[u8],
+    w: &mut W,
+) -> Result<()> {
     if val.should_write() {
         write_field_name(field_name, w)?;
         write_epee_value(val, w)?;
@@ -182,23 +488,75 @@ pub fn write_field<T: EpeeValue, W: Write>(val: &T, field_name: &str, w: &mut W)
     Ok(())
 }
 
-fn read_object<T: EpeeObject, R: Read>(r: &mut R, skipped_objects: &mut u8) -> Result<T> {
-    let mut object_builder = T::Builder::default();
-
-    let number_o_field = read_varint(r)?;
+/// Write an epee field with a marker known at runtime, `write_value` is called to
+/// write the value after the marker so it must write a value matching `marker`.
+///
+/// This is for values which are not an [`EpeeValue`], like dynamically typed values
+/// decoded from elsewhere, unlike [`write_field`] the field is always written.
+pub fn write_field_with_marker<W: Write, F: FnOnce(&mut W) -> Result<()>>(
+    field_name: &str,
+    marker: &Marker,
+    w: &mut W,
+    write_value: F,
+) -> Result<()> {
+    write_field_name(field_name.as_bytes(), w)?;
+    w.write_all(&[marker.as_u8()])?;
+    write_value(w)
+}
 
-    if number_o_field > MAX_NUM_FIELDS {
-        return Err(Error::Format(
-            "Data has object with more fields than the maximum allowed",
-        ));
+/// Write an object from an iterator of fields, the object is written in the same
+/// way as an [`EpeeObject`] (without a marker) so this can be used inside
+/// [`EpeeObjectWrite::write_fields`] or after a marker.
+///
+/// As the number of fields is written before the fields, every field is first
+/// written to a buffer which is then written to `w`, so this will allocate as much
+/// as the encoded size of the object.
+pub fn write_object_streaming<'a, W: Write>(
+    w: &mut W,
+    fields: impl Iterator<Item = (&'a str, &'a dyn EpeeValueDyn)>,
+) -> Result<()> {
+    let mut buf = Vec::new();
+    let mut number_of_fields: u64 = 0;
+
+    for (field_name, val) in fields {
+        if val.write_field_dyn(field_name, &mut buf)? {
+            number_of_fields += 1;
+        }
     }
 
+    write_varint(number_of_fields, w)?;
+    w.write_all(&buf)
+}
+
+fn read_object<T: EpeeObject, R: Read>(r: &mut R, skipped_objects: &mut u8) -> Result<T> {
+    read_object_with(r, T::Builder::default(), |_, r| {
+        skip_epee_value_counted(r, skipped_objects)
+    })
+}
+
+/// Read an object, calling `unknown_field` for every field the object's builder does not need.
+fn read_object_with<T: EpeeObject, R: Read, F: FnMut(&str, &mut R) -> Result<()>>(
+    r: &mut R,
+    mut object_builder: T::Builder,
+    mut unknown_field: F,
+) -> Result<T> {
+    let number_o_field = read_varint_bounded(r, r.decode_options().max_fields)?;
+    r.add_fields(number_o_field);
+
+    let mut name_buf = [0; 255];
     for _ in 0..number_o_field {
-        let field_name = read_field_name(r)?;
+        let field_name = read_field_name(r, &mut name_buf)?;
 
-        if !object_builder.add_field(&field_name, r)? {
-            skip_epee_value(r, skipped_objects)?;
-        }
+        let res = match object_builder.add_field(field_name, r) {
+            Ok(true) => Ok(()),
+            Ok(false) => unknown_field(field_name, r),
+            Err(e) => Err(e),
+        };
+
+        res.map_err(|error| Error::Field {
+            name: field_name.into(),
+            error: Box::new(error),
+        })?;
     }
     object_builder.finish()
 }
@@ -216,13 +574,85 @@ pub fn read_epee_value<T: EpeeValue, R: Read>(r: &mut R) -> Result<T> {
     T::read(r, &marker)
 }
 
+/// Like [`read_epee_value`] but also returns the marker the value was read with.
+pub fn read_epee_value_and_marker<T: EpeeValue, R: Read>(r: &mut R) -> Result<(T, Marker)> {
+    let marker = read_marker(r)?;
+    let val = T::read(r, &marker)?;
+    Ok((val, marker))
+}
+
+/// Read an epee value into `val`, reusing its allocations where possible, see
+/// [`EpeeValue::read_into`].
+pub fn read_epee_value_into<T: EpeeValue, R: Read>(val: &mut T, r: &mut R) -> Result<()> {
+    let marker = read_marker(r)?;
+    val.read_into(r, &marker)
+}
+
+/// Like [`read_epee_value`] but first checks the marker is for `expected`, returning
+/// an [`Error::UnexpectedMarker`] naming both markers if it isn't.
+///
+/// Only the type of the marker is checked, `T` still checks if the marker is a sequence.
+pub fn read_epee_value_expecting<T: EpeeValue, R: Read>(
+    r: &mut R,
+    expected: InnerMarker,
+) -> Result<T> {
+    let marker = read_marker(r)?;
+    if marker.inner_marker != expected {
+        return Err(Error::UnexpectedMarker {
+            expected: Marker {
+                inner_marker: expected,
+                is_seq: marker.is_seq,
+            },
+            found: marker,
+        });
+    }
+    T::read(r, &marker)
+}
+
 /// Write an epee value to the stream, an epee value is the part after the key
 /// including the marker.
 fn write_epee_value<T: EpeeValue, W: Write>(val: &T, w: &mut W) -> Result<()> {
-    w.write_all(&[T::MARKER.as_u8()])?;
+    w.write_all(&[val.marker().as_u8()])?;
     val.write(w)
 }
 
+/// Read a string epee value, replacing invalid UTF-8 with `U+FFFD` instead of
+/// erroring. Some nodes send string fields which are not valid UTF-8, to keep the
+/// exact bytes read the field as a `Vec<u8>`.
+///
+/// Like [`read_epee_value`] this reads the marker as well as the value.
+pub fn read_string_lossy<R: Read>(r: &mut R) -> Result<String> {
+    let bytes: Vec<u8> = read_epee_value(r)?;
+    Ok(match String::from_utf8(bytes) {
+        Ok(string) => string,
+        Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+    })
+}
+
+/// Read a byte array (string marked) epee value from a byte slice without copying it,
+/// the returned bytes are borrowed from the original buffer.
+///
+/// Like [`read_epee_value`] this reads the marker as well as the value.
+pub fn read_epee_bytes_borrowed<'a>(r: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let marker = read_marker(r)?;
+    if marker != Vec::<u8>::MARKER {
+        return Err(Error::UnexpectedMarker {
+            expected: Vec::<u8>::MARKER,
+            found: marker,
+        });
+    }
+
+    let len = read_varint_bounded(r, r.decode_options().max_byte_array_len)?;
+    let len = len_to_usize(len)?;
+    if r.len() < len {
+        return Err(Error::IO("Reader ran out of bytes"));
+    }
+
+    let (bytes, rest) = r.split_at(len);
+    *r = rest;
+    Ok(bytes)
+}
+
 /// A helper object builder that just skips every field.
 #[derive(Default)]
 struct SkipObjectBuilder;
@@ -242,7 +672,9 @@ struct SkipObject;
 
 impl EpeeObject for SkipObject {
     type Builder = SkipObjectBuilder;
+}
 
+impl EpeeObjectWrite for SkipObject {
     fn number_of_fields(&self) -> u64 {
         panic!("This is a helper function to use when de-serialising")
     }
@@ -252,13 +684,34 @@ impl EpeeObject for SkipObject {
     }
 }
 
-/// Skip an epee value, should be used when you do not need the value
-/// stored at a key.
-fn skip_epee_value<R: Read>(r: &mut R, skipped_objects: &mut u8) -> Result<()> {
+/// Skip an epee value, including the marker. This can be used in
+/// [`EpeeObjectBuilder::add_field`] to read and discard the value of a field which
+/// is not needed.
+pub fn skip_epee_value<R: Read>(r: &mut R) -> Result<()> {
+    skip_epee_value_counted(r, &mut 0)
+}
+
+/// Skip an epee value, `skipped_objects` is the depth of objects currently being
+/// skipped.
+fn skip_epee_value_counted<R: Read>(r: &mut R, skipped_objects: &mut u8) -> Result<()> {
     let marker = read_marker(r)?;
+    skip_epee_value_with_marker(r, &marker, skipped_objects)
+}
+
+/// Skip an epee value after its marker has been read.
+fn skip_epee_value_with_marker<R: Read>(
+    r: &mut R,
+    marker: &Marker,
+    skipped_objects: &mut u8,
+) -> Result<()> {
+    if marker.inner_marker == InnerMarker::Object && !r.is_limited() {
+        return skip_epee_value_with_marker(&mut limited(r), marker, skipped_objects);
+    }
+
     let mut len = 1;
     if marker.is_seq {
         len = read_varint(r)?;
+        r.add_seq_elements(len)?;
     }
     for _ in 0..len {
         match marker.inner_marker {
@@ -275,14 +728,18 @@ fn skip_epee_value<R: Read>(r: &mut R, skipped_objects: &mut u8) -> Result<()> {
                 read_bytes::<_, 1>(r)?;
             }
             InnerMarker::String => {
-                Vec::<u8>::read(r, &marker)?;
+                Vec::<u8>::read(r, &Vec::<u8>::MARKER)?;
             }
             InnerMarker::Object => {
-                *skipped_objects += 1;
-                if *skipped_objects > MAX_DEPTH_OF_SKIPPED_OBJECTS {
+                // Compared before incrementing so `u8::MAX` can be used as the maximum.
+                if *skipped_objects >= r.decode_options().max_skipped_object_depth {
                     return Err(Error::Format("Depth of skipped objects exceeded maximum"));
                 }
+                *skipped_objects += 1;
+                r.skip_object();
+                r.enter_object()?;
                 read_object::<SkipObject, _>(r, skipped_objects)?;
+                r.exit_object();
                 *skipped_objects -= 1;
             }
         };
@@ -1,4 +1,6 @@
 /// This module contains a [`Marker`] which is appended before each value to tell you the type.
+use core::fmt::{Display, Formatter};
+
 use crate::Error;
 
 /// The inner marker just telling you the type.
@@ -27,12 +29,37 @@ pub struct Marker {
 }
 
 impl Marker {
-    pub(crate) const fn new(inner_marker: InnerMarker) -> Self {
+    /// Create a new non-sequence [`Marker`] for the given type.
+    pub const fn new(inner_marker: InnerMarker) -> Self {
         Marker {
             inner_marker,
             is_seq: false,
         }
     }
+
+    /// Returns the type of the value(s) this marker is for.
+    pub const fn inner_marker(&self) -> &InnerMarker {
+        &self.inner_marker
+    }
+
+    /// Returns true if this marker is for a sequence of values.
+    pub const fn is_seq(&self) -> bool {
+        self.is_seq
+    }
+
+    /// Returns the marker for a sequence of values with this marker.
+    ///
+    /// A sequence of `U8`s returns the `String` marker, as byte arrays are encoded as
+    /// strings.
+    ///
+    /// To check at runtime use [`Marker::try_into_seq`].
+    ///
+    /// # Panics
+    ///
+    /// Epee has no sequences of sequences so this panics if this marker is already for a
+    /// sequence. This crate only calls it in the `const MARKER` of sequence types, where a
+    /// sequence of sequences is a compile error instead of a panic, so no marker made by this
+    /// crate can panic at runtime.
     pub const fn into_seq(self) -> Self {
         if self.is_seq {
             panic!("Sequence of sequence not allowed!");
@@ -50,6 +77,18 @@ impl Marker {
         }
     }
 
+    /// Returns the marker for a sequence of values with this marker, or an error if this
+    /// marker is already for a sequence.
+    ///
+    /// A sequence of `U8`s returns the `String` marker, as byte arrays are encoded as
+    /// strings.
+    pub fn try_into_seq(self) -> Result<Self, Error> {
+        if self.is_seq {
+            return Err(Error::Format("Sequence of sequence not allowed"));
+        }
+        Ok(self.into_seq())
+    }
+
     pub const fn as_u8(&self) -> u8 {
         let marker_val = match self.inner_marker {
             InnerMarker::I64 => 1,
@@ -74,6 +113,37 @@ impl Marker {
     }
 }
 
+impl Display for InnerMarker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let name = match self {
+            InnerMarker::I64 => "i64",
+            InnerMarker::I32 => "i32",
+            InnerMarker::I16 => "i16",
+            InnerMarker::I8 => "i8",
+            InnerMarker::U64 => "u64",
+            InnerMarker::U32 => "u32",
+            InnerMarker::U16 => "u16",
+            InnerMarker::U8 => "u8",
+            InnerMarker::F64 => "f64",
+            InnerMarker::String => "string",
+            InnerMarker::Bool => "bool",
+            InnerMarker::Object => "object",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A marker is displayed as its type, sequences are displayed as `seq<type>`.
+impl Display for Marker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if self.is_seq {
+            write!(f, "seq<{}>", self.inner_marker)
+        } else {
+            write!(f, "{}", self.inner_marker)
+        }
+    }
+}
+
 impl TryFrom<u8> for Marker {
     type Error = Error;
 
@@ -97,6 +167,8 @@ impl TryFrom<u8> for Marker {
             10 => InnerMarker::String,
             11 => InnerMarker::Bool,
             12 => InnerMarker::Object,
+            // monerod's marker for a sequence, only used as the type of a sequence's elements.
+            13 => return Err(Error::Format("Sequences of sequences are not supported")),
             _ => return Err(Error::Format("Unknown value Marker")),
         };
 
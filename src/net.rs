@@ -0,0 +1,343 @@
+/// This module contains epee impls for the std network address types.
+///
+/// The layouts match monerod's `net_utils` address types so peer lists can be
+/// decoded directly into std types.
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use crate::io::*;
+use crate::{
+    read_epee_value, read_marker, write_field, EpeeObject, EpeeObjectBuilder, EpeeObjectWrite,
+    EpeeValue, Error, InnerMarker, Result,
+};
+
+/// The `type` of an IPv4 address, matching monerod's `address_type::ipv4`.
+const IPV4_TYPE: u8 = 1;
+/// The `type` of an IPv6 address, matching monerod's `address_type::ipv6`.
+const IPV6_TYPE: u8 = 2;
+
+/// A [`SocketAddrV4`] is encoded as an object matching monerod's
+/// `ipv4_network_address`:
+///
+/// - `m_ip`: the address as a `u32`, see the [`Ipv4Addr`] `EpeeValue` impl.
+/// - `m_port`: the port as a `u16`.
+impl EpeeObject for SocketAddrV4 {
+    type Builder = SocketAddrV4Builder;
+}
+
+impl EpeeObjectWrite for SocketAddrV4 {
+    fn number_of_fields(&self) -> u64 {
+        2
+    }
+
+    fn write_fields<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_field(self.ip(), "m_ip", w)?;
+        write_field(&self.port(), "m_port", w)
+    }
+
+    fn size_hint(&self) -> usize {
+        // `m_ip` and `m_port` with their names and markers.
+        (1 + 4 + 1 + 4) + (1 + 6 + 1 + 2)
+    }
+}
+
+#[derive(Default)]
+pub struct SocketAddrV4Builder {
+    ip: Option<Ipv4Addr>,
+    port: Option<u16>,
+}
+
+impl EpeeObjectBuilder<SocketAddrV4> for SocketAddrV4Builder {
+    fn add_field<R: Read>(&mut self, name: &str, r: &mut R) -> Result<bool> {
+        match name {
+            "m_ip" => {
+                if self.ip.replace(read_epee_value(r)?).is_some() {
+                    return Err(Error::Format("Double key in data!"));
+                }
+            }
+            "m_port" => {
+                if self.port.replace(read_epee_value(r)?).is_some() {
+                    return Err(Error::Format("Double key in data!"));
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn finish(self) -> Result<SocketAddrV4> {
+        Ok(SocketAddrV4::new(
+            self.ip
+                .ok_or(Error::Format("Required field `m_ip` was not found!"))?,
+            self.port
+                .ok_or(Error::Format("Required field `m_port` was not found!"))?,
+        ))
+    }
+}
+
+/// An [`IpAddr`] is encoded as an object with the address family and the address:
+///
+/// - `type`: `1` for IPv4 and `2` for IPv6, as a `u8`.
+/// - `addr`: the address, see the [`Ipv4Addr`] and [`Ipv6Addr`] `EpeeValue` impls.
+///
+/// monerod has no type for an address without a port so this layout is specific to this
+/// crate, use [`SocketAddr`] to talk to monerod.
+impl EpeeObject for IpAddr {
+    type Builder = IpAddrBuilder;
+}
+
+impl EpeeObjectWrite for IpAddr {
+    fn number_of_fields(&self) -> u64 {
+        2
+    }
+
+    fn write_fields<W: Write>(&self, w: &mut W) -> Result<()> {
+        match self {
+            IpAddr::V4(ip) => {
+                write_field(&IPV4_TYPE, "type", w)?;
+                write_field(ip, "addr", w)
+            }
+            IpAddr::V6(ip) => {
+                write_field(&IPV6_TYPE, "type", w)?;
+                write_field(ip, "addr", w)
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct IpAddrBuilder {
+    ty: Option<u8>,
+    addr: Option<IpAddr>,
+}
+
+impl EpeeObjectBuilder<IpAddr> for IpAddrBuilder {
+    fn add_field<R: Read>(&mut self, name: &str, r: &mut R) -> Result<bool> {
+        match name {
+            "type" => {
+                if self.ty.replace(read_epee_value(r)?).is_some() {
+                    return Err(Error::Format("Double key in data!"));
+                }
+            }
+            "addr" => {
+                // The address family is known from the marker so `type` can come after `addr`.
+                let marker = read_marker(r)?;
+                let addr = match marker.inner_marker {
+                    InnerMarker::U32 => IpAddr::V4(Ipv4Addr::read(r, &marker)?),
+                    _ => IpAddr::V6(Ipv6Addr::read(r, &marker)?),
+                };
+                if self.addr.replace(addr).is_some() {
+                    return Err(Error::Format("Double key in data!"));
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn finish(self) -> Result<IpAddr> {
+        let ty = self
+            .ty
+            .ok_or(Error::Format("Required field `type` was not found!"))?;
+        let addr = self
+            .addr
+            .ok_or(Error::Format("Required field `addr` was not found!"))?;
+
+        match (ty, addr) {
+            (IPV4_TYPE, IpAddr::V4(_)) | (IPV6_TYPE, IpAddr::V6(_)) => Ok(addr),
+            (IPV4_TYPE | IPV6_TYPE, _) => {
+                Err(Error::Format("IP address does not match the address type"))
+            }
+            _ => Err(Error::Value("Unknown IP address type")),
+        }
+    }
+}
+
+/// A [`SocketAddrV6`] is encoded as an object matching monerod's
+/// `ipv6_network_address`:
+///
+/// - `addr`: the address as 16 bytes, see the [`Ipv6Addr`] `EpeeValue` impl.
+/// - `m_port`: the port as a `u16`.
+///
+/// The flow info and scope id are not encoded, so they are `0` when decoded.
+impl EpeeObject for SocketAddrV6 {
+    type Builder = SocketAddrV6Builder;
+}
+
+impl EpeeObjectWrite for SocketAddrV6 {
+    fn number_of_fields(&self) -> u64 {
+        2
+    }
+
+    fn write_fields<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_field(self.ip(), "addr", w)?;
+        write_field(&self.port(), "m_port", w)
+    }
+
+    fn size_hint(&self) -> usize {
+        // `addr` and `m_port` with their names and markers.
+        (1 + 4 + 1 + 1 + 16) + (1 + 6 + 1 + 2)
+    }
+}
+
+#[derive(Default)]
+pub struct SocketAddrV6Builder {
+    fields: SocketAddrFields,
+}
+
+impl EpeeObjectBuilder<SocketAddrV6> for SocketAddrV6Builder {
+    fn add_field<R: Read>(&mut self, name: &str, r: &mut R) -> Result<bool> {
+        self.fields.add_field(name, r)
+    }
+
+    fn finish(self) -> Result<SocketAddrV6> {
+        self.fields.finish_v6()
+    }
+}
+
+/// A [`SocketAddr`] is encoded as an object matching monerod's `network_address`:
+///
+/// - `type`: `1` for IPv4 and `2` for IPv6, as a `u8`.
+/// - `addr`: an object with the address and port, see the [`SocketAddrV4`] and
+///   [`SocketAddrV6`] `EpeeObject` impls.
+impl EpeeObject for SocketAddr {
+    type Builder = SocketAddrBuilder;
+}
+
+impl EpeeObjectWrite for SocketAddr {
+    fn number_of_fields(&self) -> u64 {
+        2
+    }
+
+    fn write_fields<W: Write>(&self, w: &mut W) -> Result<()> {
+        match self {
+            SocketAddr::V4(addr) => {
+                write_field(&IPV4_TYPE, "type", w)?;
+                write_field(addr, "addr", w)
+            }
+            SocketAddr::V6(addr) => {
+                write_field(&IPV6_TYPE, "type", w)?;
+                write_field(addr, "addr", w)
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SocketAddrBuilder {
+    ty: Option<u8>,
+    addr: Option<SocketAddrFields>,
+}
+
+impl EpeeObjectBuilder<SocketAddr> for SocketAddrBuilder {
+    fn add_field<R: Read>(&mut self, name: &str, r: &mut R) -> Result<bool> {
+        match name {
+            "type" => {
+                if self.ty.replace(read_epee_value(r)?).is_some() {
+                    return Err(Error::Format("Double key in data!"));
+                }
+            }
+            "addr" => {
+                // The layout of `addr` depends on `type` which can come after `addr`, so
+                // the fields of both layouts are read.
+                if self.addr.replace(read_epee_value(r)?).is_some() {
+                    return Err(Error::Format("Double key in data!"));
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn finish(self) -> Result<SocketAddr> {
+        let ty = self
+            .ty
+            .ok_or(Error::Format("Required field `type` was not found!"))?;
+        let addr = self
+            .addr
+            .ok_or(Error::Format("Required field `addr` was not found!"))?;
+
+        match ty {
+            IPV4_TYPE => Ok(SocketAddr::V4(addr.finish_v4()?)),
+            IPV6_TYPE => Ok(SocketAddr::V6(addr.finish_v6()?)),
+            _ => Err(Error::Value("Unknown IP address type")),
+        }
+    }
+}
+
+/// The fields of both a [`SocketAddrV4`] and a [`SocketAddrV6`], used to read the
+/// `addr` of a [`SocketAddr`] before its type is known.
+#[derive(Default)]
+pub struct SocketAddrFields {
+    ip: Option<Ipv4Addr>,
+    addr: Option<Ipv6Addr>,
+    port: Option<u16>,
+}
+
+impl SocketAddrFields {
+    fn finish_v4(self) -> Result<SocketAddrV4> {
+        Ok(SocketAddrV4::new(
+            self.ip
+                .ok_or(Error::Format("Required field `m_ip` was not found!"))?,
+            self.port
+                .ok_or(Error::Format("Required field `m_port` was not found!"))?,
+        ))
+    }
+
+    fn finish_v6(self) -> Result<SocketAddrV6> {
+        Ok(SocketAddrV6::new(
+            self.addr
+                .ok_or(Error::Format("Required field `addr` was not found!"))?,
+            self.port
+                .ok_or(Error::Format("Required field `m_port` was not found!"))?,
+            0,
+            0,
+        ))
+    }
+}
+
+impl EpeeObject for SocketAddrFields {
+    type Builder = SocketAddrFields;
+}
+
+impl EpeeObjectWrite for SocketAddrFields {
+    fn number_of_fields(&self) -> u64 {
+        [self.ip.is_some(), self.addr.is_some(), self.port.is_some()]
+            .iter()
+            .filter(|is_some| **is_some)
+            .count() as u64
+    }
+
+    fn write_fields<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_field(&self.ip, "m_ip", w)?;
+        write_field(&self.addr, "addr", w)?;
+        write_field(&self.port, "m_port", w)
+    }
+}
+
+impl EpeeObjectBuilder<SocketAddrFields> for SocketAddrFields {
+    fn add_field<R: Read>(&mut self, name: &str, r: &mut R) -> Result<bool> {
+        match name {
+            "m_ip" => {
+                if self.ip.replace(read_epee_value(r)?).is_some() {
+                    return Err(Error::Format("Double key in data!"));
+                }
+            }
+            "addr" => {
+                if self.addr.replace(read_epee_value(r)?).is_some() {
+                    return Err(Error::Format("Double key in data!"));
+                }
+            }
+            "m_port" => {
+                if self.port.replace(read_epee_value(r)?).is_some() {
+                    return Err(Error::Format("Double key in data!"));
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn finish(self) -> Result<SocketAddrFields> {
+        Ok(self)
+    }
+}
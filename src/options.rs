@@ -0,0 +1,85 @@
+/// This module contains [`DecodeOptions`] which set the limits used when decoding.
+use crate::{
+    MAX_DEPTH_OF_SKIPPED_OBJECTS, MAX_NUM_FIELDS, MAX_OBJECT_DEPTH, MAX_STRING_LEN_POSSIBLE,
+};
+
+/// The limits and options used when decoding, the defaults are used by [`from_bytes`](crate::from_bytes),
+/// to use different limits see [`from_bytes_with_options`](crate::from_bytes_with_options).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DecodeOptions {
+    pub(crate) max_skipped_object_depth: u8,
+    pub(crate) max_object_depth: u8,
+    pub(crate) max_objects: u64,
+    pub(crate) max_fields: u64,
+    pub(crate) max_seq_elements: u64,
+    pub(crate) max_byte_array_len: u64,
+    pub(crate) canonicalize_nan: bool,
+}
+
+impl DecodeOptions {
+    /// The default limits.
+    pub const DEFAULT: DecodeOptions = DecodeOptions {
+        max_skipped_object_depth: MAX_DEPTH_OF_SKIPPED_OBJECTS,
+        max_object_depth: MAX_OBJECT_DEPTH,
+        max_objects: u64::MAX,
+        max_fields: MAX_NUM_FIELDS,
+        max_seq_elements: u64::MAX,
+        max_byte_array_len: MAX_STRING_LEN_POSSIBLE,
+        canonicalize_nan: false,
+    };
+
+    /// Sets the maximum depth of nested objects that are not needed and so are skipped,
+    /// defaults to 20.
+    pub fn max_skipped_object_depth(mut self, max: u8) -> Self {
+        self.max_skipped_object_depth = max;
+        self
+    }
+
+    /// Sets the maximum depth of nested objects, defaults to 100.
+    pub fn max_object_depth(mut self, max: u8) -> Self {
+        self.max_object_depth = max;
+        self
+    }
+
+    /// Sets the maximum number of objects in total, not including the top level
+    /// object, defaults to no limit.
+    pub fn max_objects(mut self, max: u64) -> Self {
+        self.max_objects = max;
+        self
+    }
+
+    /// Sets the maximum number of fields in a single object, defaults to 1000.
+    pub fn max_fields(mut self, max: u64) -> Self {
+        self.max_fields = max;
+        self
+    }
+
+    /// Sets the maximum number of sequence elements in total, including elements
+    /// of skipped sequences, defaults to no limit.
+    pub fn max_seq_elements(mut self, max: u64) -> Self {
+        self.max_seq_elements = max;
+        self
+    }
+
+    /// Sets the maximum length of a single byte array or string, defaults to 2000000000.
+    pub fn max_byte_array_len(mut self, max: u64) -> Self {
+        self.max_byte_array_len = max;
+        self
+    }
+
+    /// Sets if every NaN `f64` (and `f32`) should be read as [`f64::NAN`] instead of
+    /// keeping the bits in the data, defaults to false.
+    ///
+    /// Values are always written with their exact bits, so decoding with this set then
+    /// encoding gives the same bytes for any NaN.
+    pub fn canonicalize_nan(mut self, canonicalize: bool) -> Self {
+        self.canonicalize_nan = canonicalize;
+        self
+    }
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        DecodeOptions::DEFAULT
+    }
+}
@@ -0,0 +1,37 @@
+/// This module contains [`PendingFields`], the fields of a derived enum read before
+/// its tag.
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::io::*;
+use crate::{skip_epee_value, EpeeObjectBuilder, Result};
+
+/// The fields of a derived enum read before its tag, the variant is only known once the
+/// tag is read so these fields are kept as the bytes of their values and given to the
+/// variant's builder after the tag.
+#[derive(Default)]
+pub struct PendingFields(Vec<(String, Vec<u8>)>);
+
+impl PendingFields {
+    /// Reads the value of the field `name`, keeping its bytes.
+    pub fn push<R: Read>(&mut self, name: &str, r: &mut R) -> Result<()> {
+        let mut recorder = RecordingReader::new(r);
+        skip_epee_value(&mut recorder)?;
+        self.0.push((name.into(), recorder.into_bytes()));
+        Ok(())
+    }
+
+    /// Gives the kept fields to `builder`, the values are read with the limits of `r`.
+    /// Fields the builder doesn't need are ignored, as they would be if they were after
+    /// the tag.
+    pub fn replay<T, B: EpeeObjectBuilder<T>, R: Read>(
+        self,
+        builder: &mut B,
+        r: &mut R,
+    ) -> Result<()> {
+        for (name, bytes) in self.0 {
+            builder.add_field(&name, &mut ReplayReader::new(&bytes, r))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+//! This module contains [`DecodeStats`] which describe the data that was decoded.
+
+/// Statistics about decoded data, returned by [`from_bytes_with_stats`](crate::from_bytes_with_stats).
+///
+/// These are counted while decoding so they can be used to find unusually complex data.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct DecodeStats {
+    /// The number of objects, including skipped objects but not the top level object.
+    pub objects: u64,
+    /// The number of objects skipped because they were not needed, objects inside
+    /// skipped objects are counted.
+    pub skipped_objects: u64,
+    /// The number of fields in every object, including the top level object and
+    /// skipped objects.
+    pub fields: u64,
+    /// The number of sequence elements, including elements of skipped sequences.
+    pub seq_elements: u64,
+    /// The deepest level of nested objects, the top level object is not counted so
+    /// an object without objects as fields has a depth of 0.
+    pub max_depth: u8,
+}
@@ -0,0 +1,104 @@
+/// This module contains epee impls for tuples.
+///
+/// A tuple is encoded as an object with the element's index as the field name
+/// (`"0"`, `"1"`, ...), the same way a derived tuple struct is encoded. The unit
+/// type is an object with no fields.
+use crate::io::*;
+use crate::{
+    read_epee_value, write_field, EpeeObject, EpeeObjectBuilder, EpeeObjectWrite, EpeeValue, Error,
+    Result,
+};
+
+macro_rules! epee_tuple {
+    ($builder: ident, $(($ty: ident, $idx: tt)),+) => {
+        impl<$($ty: EpeeValue),+> EpeeObject for ($($ty,)+) {
+            type Builder = $builder<$($ty),+>;
+        }
+
+        impl<$($ty: EpeeValue),+> EpeeObjectWrite for ($($ty,)+) {
+            fn number_of_fields(&self) -> u64 {
+                let mut numb_o_fields = 0;
+                $(
+                    if self.$idx.should_write() {
+                        numb_o_fields += 1;
+                    }
+                )+
+                numb_o_fields
+            }
+
+            fn write_fields<W: Write>(&self, w: &mut W) -> Result<()> {
+                $(
+                    write_field(&self.$idx, stringify!($idx), w)?;
+                )+
+                Ok(())
+            }
+        }
+
+        pub struct $builder<$($ty),+>($((Option<$ty>, bool),)+);
+
+        impl<$($ty: EpeeValue),+> Default for $builder<$($ty),+> {
+            fn default() -> Self {
+                // Start with the default values so elements which are not written when
+                // empty (like sequences) don't cause a missing field error.
+                $builder($(($ty::epee_default_value(), false),)+)
+            }
+        }
+
+        impl<$($ty: EpeeValue),+> EpeeObjectBuilder<($($ty,)+)> for $builder<$($ty),+> {
+            fn add_field<R: Read>(&mut self, name: &str, r: &mut R) -> Result<bool> {
+                match name {
+                    $(
+                        stringify!($idx) => {
+                            if self.$idx.1 {
+                                return Err(Error::Format("Double key in data!"));
+                            }
+                            self.$idx = (Some(read_epee_value(r)?), true);
+                        }
+                    )+
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            }
+
+            fn finish(self) -> Result<($($ty,)+)> {
+                Ok(($(
+                    self.$idx.0.ok_or(Error::Format(concat!(
+                        "Required field `",
+                        stringify!($idx),
+                        "` was not found!"
+                    )))?,
+                )+))
+            }
+        }
+    };
+}
+
+epee_tuple!(Tuple2Builder, (A, 0), (B, 1));
+epee_tuple!(Tuple3Builder, (A, 0), (B, 1), (C, 2));
+
+impl EpeeObject for () {
+    type Builder = UnitBuilder;
+}
+
+impl EpeeObjectWrite for () {
+    fn number_of_fields(&self) -> u64 {
+        0
+    }
+
+    fn write_fields<W: Write>(&self, _w: &mut W) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct UnitBuilder;
+
+impl EpeeObjectBuilder<()> for UnitBuilder {
+    fn add_field<R: Read>(&mut self, _name: &str, _r: &mut R) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn finish(self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -1,22 +1,52 @@
 /// This module contains a `sealed` [`EpeeValue`] trait and different impls for
 /// the different possible base epee values.
-use alloc::string::String;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
 use alloc::vec::Vec;
-use core::fmt::Debug;
+use core::cell::{Cell, RefCell};
+use core::marker::PhantomData;
+use core::num::{
+    NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8,
+    Wrapping,
+};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::net::{Ipv4Addr, Ipv6Addr};
 
 use sealed::sealed;
 
 use crate::io::*;
 use crate::varint::*;
-use crate::{EpeeObject, Error, InnerMarker, Marker, Result, MAX_STRING_LEN_POSSIBLE};
+use crate::{
+    EpeeObject, EpeeObjectBuilder, EpeeObjectWrite, Error, InnerMarker, Marker, Result, Value,
+    MAX_STRING_LEN_POSSIBLE,
+};
 
 /// A trait for epee values, this trait is sealed as all possible epee values are
 /// defined in the lib, to make an [`EpeeValue`] outside the lib you will need to
-/// use the trait [`EpeeObject`].
-#[sealed]
+/// use the trait [`EpeeObject`], or derive it on a newtype which is then encoded as
+/// its field.
+///
+/// The seal is only visible to the crate so it can be re-exported for the derive macro.
+#[sealed(pub(crate))]
 pub trait EpeeValue: Sized {
     const MARKER: Marker;
 
+    /// The encoded size of every value of this type, only set for types which are
+    /// always written with [`EpeeValue::MARKER`] so the derive can write them directly.
+    ///
+    /// This is only used when writing and for size hints, reading has no fast path as
+    /// fields can come in any order so each one is still matched by name.
+    #[doc(hidden)]
+    const FIXED_SIZE: Option<usize> = None;
+
     fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self>;
 
     fn should_write(&self) -> bool {
@@ -32,47 +62,159 @@ pub trait EpeeValue: Sized {
         None
     }
 
+    /// Returns the marker to write before this value, this is [`EpeeValue::MARKER`]
+    /// for every type apart from ones where the type is only known at runtime.
+    fn marker(&self) -> Marker {
+        Self::MARKER
+    }
+
+    /// Read a value into `self`, reusing `self`'s allocations where possible. By
+    /// default this replaces `self` with the value from [`EpeeValue::read`].
+    ///
+    /// If an error is returned `self` is left in an unspecified state.
+    fn read_into<R: Read>(&mut self, r: &mut R, marker: &Marker) -> Result<()> {
+        *self = Self::read(r, marker)?;
+        Ok(())
+    }
+
     fn write<W: Write>(&self, w: &mut W) -> Result<()>;
 }
 
+/// An object safe version of [`EpeeValue`], this allows values of different
+/// types to be written together, see [`write_object_streaming`](crate::write_object_streaming).
+#[sealed]
+pub trait EpeeValueDyn {
+    /// Write this value as a field, returns false if the value should not be
+    /// written and so nothing was written.
+    fn write_field_dyn(&self, field_name: &str, buf: &mut Vec<u8>) -> Result<bool>;
+}
+
+#[sealed]
+impl<T: EpeeValue> EpeeValueDyn for T {
+    fn write_field_dyn(&self, field_name: &str, buf: &mut Vec<u8>) -> Result<bool> {
+        if !self.should_write() {
+            return Ok(false);
+        }
+        crate::write_field(self, field_name, buf)?;
+        Ok(true)
+    }
+}
+
 #[sealed]
 impl<T: EpeeObject> EpeeValue for T {
     const MARKER: Marker = Marker::new(InnerMarker::Object);
 
     fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
         if marker != &Self::MARKER {
-            return Err(Error::Format("Marker does not match expected Marker"));
+            return Err(Error::UnexpectedMarker {
+                expected: Self::MARKER,
+                found: marker.clone(),
+            });
+        }
+
+        if !r.is_limited() {
+            return Self::read(&mut limited(r), marker);
         }
 
+        r.enter_object()?;
         let mut skipped_objects = 0;
-        crate::read_object(r, &mut skipped_objects)
+        let res = crate::read_object(r, &mut skipped_objects);
+        r.exit_object();
+        res
     }
 
     fn write<W: Write>(&self, w: &mut W) -> Result<()> {
-        write_varint(self.number_of_fields(), w)?;
-        self.write_fields(w)
+        self.write_object(w)
+    }
+}
+
+/// A value which can be an item of a sequence, `Vec<T>`, `VecDeque<T>` and `[T; N]` are
+/// [`EpeeValue`]s for every item type. This is implemented for every value apart from
+/// sequences and the values with their own sequence impls, like `u8`. The derive macro
+/// implements it for newtypes.
+pub trait EpeeSeqItem: EpeeValue {}
+
+impl<T: EpeeObject> EpeeSeqItem for T {}
+
+/// Reads a sequence into `vec`, values already in `vec` are read into so their
+/// allocations are reused.
+fn read_seq_into<T: EpeeSeqItem, R: Read>(
+    vec: &mut Vec<T>,
+    r: &mut R,
+    marker: &Marker,
+) -> Result<()> {
+    let individual_marker = if marker.is_seq {
+        Marker::new(marker.inner_marker.clone())
+    } else if marker == &Vec::<T>::MARKER {
+        // Sequences of `U8`s, like a `Vec` of a `u8` newtype, are byte strings.
+        T::MARKER
+    } else {
+        return Err(Error::UnexpectedMarker {
+            expected: Vec::<T>::MARKER,
+            found: marker.clone(),
+        });
+    };
+    let len = read_varint(r)?;
+    if marker.is_seq {
+        r.add_seq_elements(len)?;
+    }
+
+    let len = len_to_usize(len)?;
+    vec.truncate(len);
+    vec.reserve(seq_capacity::<T>(len as u64)?.saturating_sub(vec.len()));
+    for item in vec.iter_mut() {
+        item.read_into(r, &individual_marker)?;
+    }
+    for _ in vec.len()..len {
+        vec.push(T::read(r, &individual_marker)?);
     }
+    Ok(())
+}
+
+/// Returns the capacity to allocate for a sequence of `len` items, `len` is read
+/// from the data so the capacity is limited to [`MAX_PREALLOCATED_BYTES`](crate::MAX_PREALLOCATED_BYTES).
+pub(crate) fn seq_capacity<T>(len: u64) -> Result<usize> {
+    let max_len = crate::MAX_PREALLOCATED_BYTES / core::mem::size_of::<T>().max(1);
+    Ok(len_to_usize(len)?.min(max_len))
 }
 
 #[sealed]
-impl<T: EpeeObject> EpeeValue for Vec<T> {
+impl<T: EpeeSeqItem> EpeeValue for Vec<T> {
     const MARKER: Marker = T::MARKER.into_seq();
 
     fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
-        if !marker.is_seq {
-            return Err(Error::Format(
-                "Marker is not sequence when a sequence was expected",
-            ));
-        }
-        let len = read_varint(r)?;
+        let mut res = Vec::new();
+        read_seq_into(&mut res, r, marker)?;
+        Ok(res)
+    }
 
-        let individual_marker = Marker::new(marker.inner_marker.clone());
+    fn should_write(&self) -> bool {
+        !self.is_empty()
+    }
 
-        let mut res = Vec::with_capacity(len.try_into()?);
-        for _ in 0..len {
-            res.push(T::read(r, &individual_marker)?);
+    fn epee_default_value() -> Option<Self> {
+        Some(Vec::new())
+    }
+
+    fn read_into<R: Read>(&mut self, r: &mut R, marker: &Marker) -> Result<()> {
+        read_seq_into(self, r, marker)
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_varint(self.len().try_into()?, w)?;
+        for item in self.iter() {
+            item.write(w)?;
         }
-        Ok(res)
+        Ok(())
+    }
+}
+
+#[sealed]
+impl<T: EpeeSeqItem> EpeeValue for VecDeque<T> {
+    const MARKER: Marker = T::MARKER.into_seq();
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        Ok(Vec::<T>::read(r, marker)?.into())
     }
 
     fn should_write(&self) -> bool {
@@ -80,7 +222,7 @@ impl<T: EpeeObject> EpeeValue for Vec<T> {
     }
 
     fn epee_default_value() -> Option<Self> {
-        Some(Vec::new())
+        Some(VecDeque::new())
     }
 
     fn write<W: Write>(&self, w: &mut W) -> Result<()> {
@@ -92,18 +234,30 @@ impl<T: EpeeObject> EpeeValue for Vec<T> {
     }
 }
 
+/// Converts a sequence read from the data into an array, erroring if the sequence
+/// has a different length.
+fn vec_into_array<T, const N: usize>(vec: Vec<T>) -> Result<[T; N]> {
+    vec.try_into().map_err(|vec: Vec<T>| Error::LengthMismatch {
+        expected: N as u64,
+        found: vec.len() as u64,
+    })
+}
+
 #[sealed]
-impl<T: EpeeObject + Debug, const N: usize> EpeeValue for [T; N] {
+impl<T: EpeeSeqItem, const N: usize> EpeeValue for [T; N] {
     const MARKER: Marker = <T>::MARKER.into_seq();
 
     fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
-        let vec = Vec::<T>::read(r, marker)?;
+        vec_into_array(Vec::<T>::read(r, marker)?)
+    }
 
-        if vec.len() != N {
-            return Err(Error::Format("Array has incorrect length"));
-        }
+    // Like other sequences an empty array is not written.
+    fn should_write(&self) -> bool {
+        N != 0
+    }
 
-        Ok(vec.try_into().unwrap())
+    fn epee_default_value() -> Option<Self> {
+        vec_into_array(Vec::new()).ok()
     }
 
     fn write<W: Write>(&self, w: &mut W) -> Result<()> {
@@ -120,10 +274,14 @@ macro_rules! epee_numb {
         #[sealed]
         impl EpeeValue for $numb {
             const MARKER: Marker = Marker::new(InnerMarker::$marker);
+            const FIXED_SIZE: Option<usize> = Some(core::mem::size_of::<$numb>());
 
             fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
                 if marker != &Self::MARKER {
-                    return Err(Error::Format("Marker does not match expected Marker"));
+                    return Err(Error::UnexpectedMarker {
+                        expected: Self::MARKER,
+                        found: marker.clone(),
+                    });
                 }
 
                 Ok(<$numb>::from_le_bytes(read_bytes(r)?))
@@ -144,235 +302,1173 @@ epee_numb!(u8, U8);
 epee_numb!(u16, U16);
 epee_numb!(u32, U32);
 epee_numb!(u64, U64);
-epee_numb!(f64, F64);
 
+/// An `f64` is written with its exact bits, so NaN payloads and the sign of zero are kept
+/// and `0.0` and `-0.0` are always written. To read every NaN as [`f64::NAN`] see
+/// [`DecodeOptions::canonicalize_nan`](crate::DecodeOptions::canonicalize_nan).
 #[sealed]
-impl EpeeValue for bool {
-    const MARKER: Marker = Marker::new(InnerMarker::Bool);
+impl EpeeValue for f64 {
+    const MARKER: Marker = Marker::new(InnerMarker::F64);
+    const FIXED_SIZE: Option<usize> = Some(8);
 
     fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
         if marker != &Self::MARKER {
-            return Err(Error::Format("Marker does not match expected Marker"));
+            return Err(Error::UnexpectedMarker {
+                expected: Self::MARKER,
+                found: marker.clone(),
+            });
         }
 
-        Ok(read_byte(r)? != 0)
+        let val = f64::from_le_bytes(read_bytes(r)?);
+        if val.is_nan() && r.decode_options().canonicalize_nan {
+            return Ok(f64::NAN);
+        }
+        Ok(val)
     }
 
     fn write<W: Write>(&self, w: &mut W) -> Result<()> {
-        w.write_all(&[if *self { 1 } else { 0 }])
+        w.write_all(&self.to_le_bytes())
     }
 }
 
+// Non zero ints are encoded as the inner int, when reading a zero returns an error.
+macro_rules! epee_non_zero_numb {
+    ($non_zero:ty, $numb:ty) => {
+        #[sealed]
+        impl EpeeValue for $non_zero {
+            const MARKER: Marker = <$numb>::MARKER;
+            const FIXED_SIZE: Option<usize> = <$numb>::FIXED_SIZE;
+
+            fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+                <$non_zero>::new(<$numb>::read(r, marker)?)
+                    .ok_or(Error::Value("Non zero int was zero"))
+            }
+
+            fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+                self.get().write(w)
+            }
+        }
+    };
+}
+
+epee_non_zero_numb!(NonZeroI64, i64);
+epee_non_zero_numb!(NonZeroI32, i32);
+epee_non_zero_numb!(NonZeroI16, i16);
+epee_non_zero_numb!(NonZeroI8, i8);
+epee_non_zero_numb!(NonZeroU8, u8);
+epee_non_zero_numb!(NonZeroU16, u16);
+epee_non_zero_numb!(NonZeroU32, u32);
+epee_non_zero_numb!(NonZeroU64, u64);
+
+/// Epee has no `f32` marker so an `f32` is encoded as an `f64`, this is lossless.
+///
+/// When reading, the `f64` is rounded to the nearest `f32` so precision may be lost,
+/// infinities and NaN are kept but a finite value too large for an `f32` returns an error.
 #[sealed]
-impl EpeeValue for Vec<u8> {
-    const MARKER: Marker = Marker::new(InnerMarker::String);
+impl EpeeValue for f32 {
+    const MARKER: Marker = Marker::new(InnerMarker::F64);
+    const FIXED_SIZE: Option<usize> = f64::FIXED_SIZE;
 
     fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
-        if marker != &Self::MARKER {
-            return Err(Error::Format("Marker does not match expected Marker"));
-        }
+        let val = f64::read(r, marker)?;
 
-        let len = read_varint(r)?;
-        if len > MAX_STRING_LEN_POSSIBLE {
-            return Err(Error::Format("Byte array exceeded max length"));
+        let narrowed = val as f32;
+        if narrowed.is_infinite() && val.is_finite() {
+            return Err(Error::Value("f64 is too large for an f32"));
         }
-
-        read_var_bytes(r, len.try_into()?)
+        Ok(narrowed)
     }
 
     fn write<W: Write>(&self, w: &mut W) -> Result<()> {
-        write_varint(self.len().try_into()?, w)?;
-        w.write_all(self)
+        f64::from(*self).write(w)
     }
 }
 
+/// A `char` is encoded as a `u32` holding its code point, when reading the
+/// `u32` must be a valid `char` otherwise an error is returned.
 #[sealed]
-impl EpeeValue for String {
-    const MARKER: Marker = Marker::new(InnerMarker::String);
+impl EpeeValue for char {
+    const MARKER: Marker = Marker::new(InnerMarker::U32);
+    const FIXED_SIZE: Option<usize> = u32::FIXED_SIZE;
 
     fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
-        if marker != &Self::MARKER {
-            return Err(Error::Format("Marker does not match expected Marker"));
-        }
+        char::from_u32(u32::read(r, marker)?).ok_or(Error::Value("u32 is not a valid char"))
+    }
 
-        let len = read_varint(r)?;
-        if len > MAX_STRING_LEN_POSSIBLE {
-            return Err(Error::Format("String exceeded max length"));
-        }
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        u32::from(*self).write(w)
+    }
+}
+
+/// A [`Duration`] is encoded as a `u64` of whole seconds, as monerod uses seconds for
+/// its timeouts. Writing a duration with a fractional number of seconds returns an
+/// error instead of silently dropping the fraction.
+#[sealed]
+impl EpeeValue for Duration {
+    const MARKER: Marker = Marker::new(InnerMarker::U64);
 
-        read_string(r, len.try_into()?)
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        Ok(Duration::from_secs(u64::read(r, marker)?))
     }
 
     fn write<W: Write>(&self, w: &mut W) -> Result<()> {
-        write_varint(self.len().try_into()?, w)?;
-        w.write_all(self.as_bytes())
+        if self.subsec_nanos() != 0 {
+            return Err(Error::Value("Duration has a fractional number of seconds"));
+        }
+        self.as_secs().write(w)
     }
 }
 
+// Epee has no 128 bit marker so 128 bit ints are encoded as byte arrays of
+// 16 little-endian bytes.
+macro_rules! epee_wide_numb {
+    ($numb:ty) => {
+        #[sealed]
+        impl EpeeValue for $numb {
+            const MARKER: Marker = Marker::new(InnerMarker::String);
+
+            fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+                if marker != &Self::MARKER {
+                    return Err(Error::UnexpectedMarker {
+                        expected: Self::MARKER,
+                        found: marker.clone(),
+                    });
+                }
+
+                let len = read_varint(r)?;
+                if len != 16 {
+                    return Err(Error::Format("128 bit int does not have a length of 16"));
+                }
+
+                Ok(<$numb>::from_le_bytes(read_bytes(r)?))
+            }
+
+            fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+                write_varint(16, w)?;
+                w.write_all(&self.to_le_bytes())
+            }
+        }
+    };
+}
+
+epee_wide_numb!(u128);
+epee_wide_numb!(i128);
+
+/// An [`Ipv4Addr`] is encoded as a `u32`, the same way monerod stores `m_ip`:
+/// the `u32` holds the octets in network order in memory, so when written as a
+/// little-endian integer the bytes on the wire are the octets in order.
+#[cfg(feature = "std")]
 #[sealed]
-impl<const N: usize> EpeeValue for [u8; N] {
-    const MARKER: Marker = Marker::new(InnerMarker::String);
+impl EpeeValue for Ipv4Addr {
+    const MARKER: Marker = Marker::new(InnerMarker::U32);
+    const FIXED_SIZE: Option<usize> = Some(4);
 
     fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
         if marker != &Self::MARKER {
-            return Err(Error::Format("Marker does not match expected Marker"));
-        }
-
-        let len = read_varint(r)?;
-        if len != N.try_into()? {
-            return Err(Error::Format("Byte array has incorrect length"));
+            return Err(Error::UnexpectedMarker {
+                expected: Self::MARKER,
+                found: marker.clone(),
+            });
         }
 
-        read_bytes(r)
+        Ok(Ipv4Addr::from(read_bytes::<_, 4>(r)?))
     }
 
     fn write<W: Write>(&self, w: &mut W) -> Result<()> {
-        write_varint(self.len().try_into()?, w)?;
-        w.write_all(self)
+        w.write_all(&self.octets())
     }
 }
 
+/// An [`Ipv6Addr`] is encoded as a byte array of its 16 octets, the same way
+/// monerod stores `m_address`.
+#[cfg(feature = "std")]
 #[sealed]
-impl<const N: usize> EpeeValue for Vec<[u8; N]> {
-    const MARKER: Marker = <[u8; N]>::MARKER.into_seq();
+impl EpeeValue for Ipv6Addr {
+    const MARKER: Marker = <[u8; 16]>::MARKER;
 
     fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
-        if !marker.is_seq {
-            return Err(Error::Format(
-                "Marker is not sequence when a sequence was expected",
-            ));
-        }
+        Ok(Ipv6Addr::from(<[u8; 16]>::read(r, marker)?))
+    }
 
-        let len = read_varint(r)?;
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        self.octets().write(w)
+    }
+}
 
-        let individual_marker = Marker::new(marker.inner_marker.clone());
+#[sealed]
+impl EpeeValue for bool {
+    const MARKER: Marker = Marker::new(InnerMarker::Bool);
+    const FIXED_SIZE: Option<usize> = Some(1);
 
-        let mut res = Vec::with_capacity(len.try_into()?);
-        for _ in 0..len {
-            res.push(<[u8; N]>::read(r, &individual_marker)?);
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        if marker != &Self::MARKER {
+            return Err(Error::UnexpectedMarker {
+                expected: Self::MARKER,
+                found: marker.clone(),
+            });
         }
-        Ok(res)
-    }
-
-    fn should_write(&self) -> bool {
-        !self.is_empty()
-    }
 
-    fn epee_default_value() -> Option<Self> {
-        Some(Vec::new())
+        Ok(read_byte(r)? != 0)
     }
 
     fn write<W: Write>(&self, w: &mut W) -> Result<()> {
-        write_varint(self.len().try_into()?, w)?;
-        for item in self.iter() {
-            item.write(w)?;
-        }
-        Ok(())
+        w.write_all(&[if *self { 1 } else { 0 }])
     }
 }
 
-macro_rules! epee_seq {
-    ($val:ty) => {
-        #[sealed]
-        impl EpeeValue for Vec<$val> {
-            const MARKER: Marker = <$val>::MARKER.into_seq();
-
-            fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
-                if !marker.is_seq {
-                    return Err(Error::Format(
-                        "Marker is not sequence when a sequence was expected",
-                    ));
-                }
+/// Writes `bytes` as a string/byte array, the length followed by the bytes. This is how
+/// every byte array and string type is written.
+fn write_bytes_with_len<W: Write>(bytes: &[u8], w: &mut W) -> Result<()> {
+    let len = bytes.len().try_into()?;
+    if len > MAX_STRING_LEN_POSSIBLE {
+        return Err(Error::Value("Byte array exceeded max length"));
+    }
 
-                let len = read_varint(r)?;
+    write_varint(len, w)?;
+    w.write_all(bytes)
+}
 
-                let individual_marker = Marker::new(marker.inner_marker.clone());
+#[sealed]
+impl EpeeValue for Vec<u8> {
+    const MARKER: Marker = Marker::new(InnerMarker::String);
 
-                let mut res = Vec::with_capacity(len.try_into()?);
-                for _ in 0..len {
-                    res.push(<$val>::read(r, &individual_marker)?);
-                }
-                Ok(res)
-            }
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        if marker != &Self::MARKER {
+            return Err(Error::UnexpectedMarker {
+                expected: Self::MARKER,
+                found: marker.clone(),
+            });
+        }
 
-            fn should_write(&self) -> bool {
-                !self.is_empty()
-            }
+        let len = read_varint_bounded(r, r.decode_options().max_byte_array_len)?;
 
-            fn epee_default_value() -> Option<Self> {
-                Some(Vec::new())
-            }
+        read_var_bytes(r, len_to_usize(len)?)
+    }
 
-            fn write<W: Write>(&self, w: &mut W) -> Result<()> {
-                write_varint(self.len().try_into()?, w)?;
-                for item in self.iter() {
-                    item.write(w)?;
-                }
-                Ok(())
-            }
+    fn read_into<R: Read>(&mut self, r: &mut R, marker: &Marker) -> Result<()> {
+        if marker != &Self::MARKER {
+            return Err(Error::UnexpectedMarker {
+                expected: Self::MARKER,
+                found: marker.clone(),
+            });
         }
 
-        #[sealed]
-        impl<const N: usize> EpeeValue for [$val; N] {
-            const MARKER: Marker = <$val>::MARKER.into_seq();
-
-            fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
-                let vec = Vec::<$val>::read(r, marker)?;
+        let len = read_varint_bounded(r, r.decode_options().max_byte_array_len)?;
 
-                if vec.len() != N {
-                    return Err(Error::Format("Array has incorrect length"));
-                }
+        read_var_bytes_into(r, len_to_usize(len)?, self)
+    }
 
-                Ok(vec.try_into().unwrap())
-            }
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_bytes_with_len(self, w)
+    }
+}
 
-            fn write<W: Write>(&self, w: &mut W) -> Result<()> {
-                write_varint(self.len().try_into()?, w)?;
-                for item in self.iter() {
-                    item.write(w)?;
-                }
-                Ok(())
-            }
-        }
-    };
+/// A sequence of `u8`s encoded with the `U8` sequence marker.
+///
+/// A `Vec<u8>` is encoded as a byte string with the `String` marker, which is what
+/// monerod uses for all byte arrays. Some other epee implementations send a sequence
+/// of `u8`s instead, this type is for reading and writing those.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RawU8Seq(pub Vec<u8>);
+
+impl From<Vec<u8>> for RawU8Seq {
+    fn from(val: Vec<u8>) -> Self {
+        RawU8Seq(val)
+    }
 }
 
-epee_seq!(i64);
-epee_seq!(i32);
-epee_seq!(i16);
-epee_seq!(i8);
-epee_seq!(u64);
-epee_seq!(u32);
-epee_seq!(u16);
-epee_seq!(f64);
-epee_seq!(bool);
-epee_seq!(Vec<u8>);
-epee_seq!(String);
+impl From<RawU8Seq> for Vec<u8> {
+    fn from(val: RawU8Seq) -> Self {
+        val.0
+    }
+}
 
 #[sealed]
-impl<T: EpeeValue> EpeeValue for Option<T> {
-    const MARKER: Marker = T::MARKER;
+impl EpeeValue for RawU8Seq {
+    // `into_seq` turns the `U8` marker into the `String` marker so this is made directly.
+    const MARKER: Marker = Marker {
+        inner_marker: InnerMarker::U8,
+        is_seq: true,
+    };
 
     fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
-        Ok(Some(T::read(r, marker)?))
+        if marker != &Self::MARKER {
+            return Err(Error::UnexpectedMarker {
+                expected: Self::MARKER,
+                found: marker.clone(),
+            });
+        }
+
+        let len = read_varint(r)?;
+        r.add_seq_elements(len)?;
+
+        Ok(RawU8Seq(read_var_bytes(r, len_to_usize(len)?)?))
     }
 
     fn should_write(&self) -> bool {
-        match self {
-            Some(t) => t.should_write(),
-            None => false,
-        }
+        !self.0.is_empty()
     }
 
     fn epee_default_value() -> Option<Self> {
-        Some(None)
+        Some(RawU8Seq::default())
     }
 
     fn write<W: Write>(&self, w: &mut W) -> Result<()> {
-        match self {
-            Some(t) => t.write(w)?,
-            None => panic!("Can't write an Option::None value, this should be handled elsewhere"),
+        write_varint(self.0.len().try_into()?, w)?;
+        w.write_all(&self.0)
+    }
+}
+
+/// A sequence of `bool`s bit packed into a byte string with the `String` marker.
+///
+/// A `Vec<bool>` is encoded as a `Bool` sequence using a byte per `bool`, this packs 8
+/// `bool`s into each byte instead. The first byte of the string is the number of unused
+/// bits in the last byte, followed by the bits with the first `bool` in the lowest bit.
+///
+/// This is not compatible with monerod's `Bool` sequences, it should only be used
+/// between nodes which both use this type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackedBools(pub Vec<bool>);
+
+impl From<Vec<bool>> for PackedBools {
+    fn from(val: Vec<bool>) -> Self {
+        PackedBools(val)
+    }
+}
+
+impl From<PackedBools> for Vec<bool> {
+    fn from(val: PackedBools) -> Self {
+        val.0
+    }
+}
+
+#[sealed]
+impl EpeeValue for PackedBools {
+    const MARKER: Marker = Marker::new(InnerMarker::String);
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        let bytes = Vec::<u8>::read(r, marker)?;
+
+        let (padding, packed) = match bytes.split_first() {
+            Some((padding, packed)) => (*padding, packed),
+            None => return Ok(PackedBools::default()),
+        };
+        if padding > 7 || (packed.is_empty() && padding != 0) {
+            return Err(Error::Format("Packed bools has an invalid padding length"));
+        }
+        if let Some(last) = packed.last() {
+            if padding != 0 && last >> (8 - padding) != 0 {
+                return Err(Error::Format("Packed bools has padding bits set"));
+            }
+        }
+
+        let len = packed.len() * 8 - usize::from(padding);
+        Ok(PackedBools(
+            (0..len)
+                .map(|i| packed[i / 8] & (1 << (i % 8)) != 0)
+                .collect(),
+        ))
+    }
+
+    fn should_write(&self) -> bool {
+        !self.0.is_empty()
+    }
+
+    fn epee_default_value() -> Option<Self> {
+        Some(PackedBools::default())
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        let packed_len = (self.0.len() + 7) / 8;
+        // An empty sequence is written as an empty string.
+        if packed_len == 0 {
+            return write_varint(0, w);
+        }
+
+        let mut bytes = Vec::with_capacity(1 + packed_len);
+        bytes.push((packed_len * 8 - self.0.len()) as u8);
+        for chunk in self.0.chunks(8) {
+            bytes.push(
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0, |byte, (i, bit)| byte | (u8::from(*bit) << i)),
+            );
+        }
+        bytes.write(w)
+    }
+}
+
+#[sealed]
+impl EpeeValue for String {
+    const MARKER: Marker = Marker::new(InnerMarker::String);
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        if marker != &Self::MARKER {
+            return Err(Error::UnexpectedMarker {
+                expected: Self::MARKER,
+                found: marker.clone(),
+            });
+        }
+
+        let len = read_varint_bounded(r, r.decode_options().max_byte_array_len)?;
+
+        read_string(r, len_to_usize(len)?)
+    }
+
+    fn read_into<R: Read>(&mut self, r: &mut R, marker: &Marker) -> Result<()> {
+        if marker != &Self::MARKER {
+            return Err(Error::UnexpectedMarker {
+                expected: Self::MARKER,
+                found: marker.clone(),
+            });
+        }
+
+        let len = read_varint_bounded(r, r.decode_options().max_byte_array_len)?;
+
+        let mut buf = core::mem::take(self).into_bytes();
+        read_var_bytes_into(r, len_to_usize(len)?, &mut buf)?;
+        *self = String::from_utf8(buf).map_err(|_| Error::Format("Invalid string"))?;
+        Ok(())
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_bytes_with_len(self.as_bytes(), w)
+    }
+}
+
+/// A `Cow<str>` is encoded the same as a `String`, when reading the
+/// `Cow::Owned` variant is always returned.
+#[sealed]
+impl<'a> EpeeValue for Cow<'a, str> {
+    const MARKER: Marker = String::MARKER;
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        Ok(Cow::Owned(String::read(r, marker)?))
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_bytes_with_len(self.as_bytes(), w)
+    }
+}
+
+/// A `Cow<[u8]>` is encoded the same as a `Vec<u8>`, when reading the
+/// `Cow::Owned` variant is always returned.
+#[sealed]
+impl<'a> EpeeValue for Cow<'a, [u8]> {
+    const MARKER: Marker = Vec::<u8>::MARKER;
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        Ok(Cow::Owned(Vec::<u8>::read(r, marker)?))
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_bytes_with_len(self, w)
+    }
+}
+
+/// A reference field of a write only derived object, see [`EpeeObjectWrite`]. A `&str`
+/// is written like a `String`, a `&[u8]` like a `Vec<u8>` and a reference to an object
+/// like the object.
+///
+/// The derive only uses this to write fields of objects without a builder, so it is never
+/// read.
+#[doc(hidden)]
+pub struct Borrowed<'a, T: ?Sized>(pub &'a T);
+
+/// The error returned if a [`Borrowed`] is read, the derive never reads them.
+const BORROWED_READ: Error = Error::Value("Borrowed fields can't be read");
+
+#[sealed]
+impl<'a> EpeeValue for Borrowed<'a, str> {
+    const MARKER: Marker = String::MARKER;
+
+    fn read<R: Read>(_r: &mut R, _marker: &Marker) -> Result<Self> {
+        Err(BORROWED_READ)
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_bytes_with_len(self.0.as_bytes(), w)
+    }
+}
+
+#[sealed]
+impl<'a> EpeeValue for Borrowed<'a, [u8]> {
+    const MARKER: Marker = Vec::<u8>::MARKER;
+
+    fn read<R: Read>(_r: &mut R, _marker: &Marker) -> Result<Self> {
+        Err(BORROWED_READ)
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_bytes_with_len(self.0, w)
+    }
+}
+
+#[sealed]
+impl<'a, T: EpeeObjectWrite + ?Sized> EpeeValue for Borrowed<'a, T> {
+    const MARKER: Marker = Marker::new(InnerMarker::Object);
+
+    fn read<R: Read>(_r: &mut R, _marker: &Marker) -> Result<Self> {
+        Err(BORROWED_READ)
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        self.0.write_object(w)
+    }
+}
+
+// `Bytes` and `BytesMut` are encoded the same as a `Vec<u8>`, when reading the bytes
+// are read into a `Vec<u8>` which is then converted, this doesn't copy for `Bytes`.
+macro_rules! epee_bytes {
+    ($bytes:ty) => {
+        #[cfg(feature = "bytes")]
+        #[sealed]
+        impl EpeeValue for $bytes {
+            const MARKER: Marker = Vec::<u8>::MARKER;
+
+            fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+                Ok(bytes::Bytes::from(Vec::<u8>::read(r, marker)?).into())
+            }
+
+            fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+                write_bytes_with_len(self, w)
+            }
+        }
+    };
+}
+
+epee_bytes!(bytes::Bytes);
+epee_bytes!(bytes::BytesMut);
+
+#[sealed]
+impl<const N: usize> EpeeValue for [u8; N] {
+    const MARKER: Marker = Marker::new(InnerMarker::String);
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        if marker != &Self::MARKER {
+            return Err(Error::UnexpectedMarker {
+                expected: Self::MARKER,
+                found: marker.clone(),
+            });
+        }
+
+        let len = read_varint(r)?;
+        if len != N.try_into()? {
+            return Err(Error::LengthMismatch {
+                expected: N as u64,
+                found: len,
+            });
+        }
+
+        read_bytes(r)
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_varint(self.len().try_into()?, w)?;
+        w.write_all(self)
+    }
+}
+
+// Byte arrays are sequence items, a `Vec<[u8; 32]>` is a sequence of byte strings.
+impl<const N: usize> EpeeSeqItem for [u8; N] {}
+
+macro_rules! epee_seq {
+    ($val:ty) => {
+        impl EpeeSeqItem for $val {}
+    };
+}
+
+epee_seq!(i64);
+epee_seq!(i32);
+epee_seq!(i16);
+epee_seq!(i8);
+epee_seq!(u64);
+epee_seq!(u32);
+epee_seq!(u16);
+epee_seq!(f64);
+epee_seq!(f32);
+epee_seq!(bool);
+epee_seq!(Vec<u8>);
+epee_seq!(String);
+epee_seq!(u128);
+epee_seq!(i128);
+#[cfg(feature = "std")]
+epee_seq!(Ipv4Addr);
+#[cfg(feature = "std")]
+epee_seq!(Ipv6Addr);
+
+#[sealed]
+impl<T: EpeeValue> EpeeValue for Option<T> {
+    const MARKER: Marker = T::MARKER;
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        Ok(Some(T::read(r, marker)?))
+    }
+
+    fn should_write(&self) -> bool {
+        match self {
+            Some(t) => t.should_write(),
+            None => false,
+        }
+    }
+
+    fn epee_default_value() -> Option<Self> {
+        Some(None)
+    }
+
+    fn marker(&self) -> Marker {
+        match self {
+            Some(t) => t.marker(),
+            None => Self::MARKER,
+        }
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        match self {
+            Some(t) => t.write(w)?,
+            None => panic!("Can't write an Option::None value, this should be handled elsewhere"),
+        }
+        Ok(())
+    }
+}
+
+/// The key of a map encoded as an object, the keys are the field names.
+trait MapKey: Sized {
+    fn from_field_name(name: &str) -> Result<Self>;
+
+    fn field_name(&self) -> Cow<'_, str>;
+}
+
+impl MapKey for String {
+    fn from_field_name(name: &str) -> Result<Self> {
+        Ok(name.into())
+    }
+
+    fn field_name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+/// Integer keys are encoded as decimal strings, when reading a field name which is not
+/// only digits, or has a leading zero, returns an error. Only the name this writes for a
+/// key is accepted so two field names can't give the same key.
+impl MapKey for u64 {
+    fn from_field_name(name: &str) -> Result<Self> {
+        if !name.bytes().all(|b| b.is_ascii_digit()) || (name.starts_with('0') && name != "0") {
+            return Err(Error::Format("Map key is not a decimal integer"));
+        }
+        name.parse()
+            .map_err(|_| Error::Format("Map key is not a decimal integer"))
+    }
+
+    fn field_name(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+}
+
+/// Reads the fields of a map object, `insert` is called for each entry and returns
+/// true if the key was already in the map.
+fn read_map_entries<K: MapKey, V: EpeeValue, R: Read>(
+    r: &mut R,
+    mut insert: impl FnMut(K, V) -> bool,
+) -> Result<()> {
+    let number_o_field = read_varint_bounded(r, r.decode_options().max_fields)?;
+    r.add_fields(number_o_field);
+
+    let mut name_buf = [0; 255];
+    for _ in 0..number_o_field {
+        let key = K::from_field_name(crate::read_field_name(r, &mut name_buf)?)?;
+        if insert(key, crate::read_epee_value(r)?) {
+            return Err(Error::Format("Double key in data!"));
+        }
+    }
+    Ok(())
+}
+
+macro_rules! epee_map {
+    ($map:ident, $key:ty) => {
+        #[sealed]
+        impl<V: EpeeValue> EpeeValue for $map<$key, V> {
+            const MARKER: Marker = Marker::new(InnerMarker::Object);
+
+            fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+                if marker != &Self::MARKER {
+                    return Err(Error::UnexpectedMarker {
+                        expected: Self::MARKER,
+                        found: marker.clone(),
+                    });
+                }
+
+                if !r.is_limited() {
+                    return Self::read(&mut limited(r), marker);
+                }
+
+                r.enter_object()?;
+                let mut res = $map::new();
+                let entries = read_map_entries(r, |key: $key, val| res.insert(key, val).is_some());
+                r.exit_object();
+                entries?;
+                Ok(res)
+            }
+
+            fn should_write(&self) -> bool {
+                !self.is_empty()
+            }
+
+            fn epee_default_value() -> Option<Self> {
+                Some($map::new())
+            }
+
+            fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+                let number_o_field = self.values().filter(|val| val.should_write()).count();
+                write_varint(number_o_field.try_into()?, w)?;
+
+                for (field_name, val) in self.iter() {
+                    crate::write_field(val, &field_name.field_name(), w)?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+// Maps are encoded as objects with each entry being a field, this allows decoding
+// objects where the field names are not known ahead of time. A `BTreeMap` is written
+// in sorted key order so its encoding is deterministic.
+#[cfg(feature = "std")]
+epee_map!(HashMap, String);
+epee_map!(BTreeMap, String);
+#[cfg(feature = "std")]
+epee_map!(HashMap, u64);
+epee_map!(BTreeMap, u64);
+
+macro_rules! epee_set {
+    ($set:ident, $($bound:tt)+) => {
+        #[sealed]
+        impl<T: EpeeValue + $($bound)+> EpeeValue for $set<T>
+        where
+            Vec<T>: EpeeValue,
+        {
+            const MARKER: Marker = Vec::<T>::MARKER;
+
+            fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+                Ok(Vec::<T>::read(r, marker)?.into_iter().collect())
+            }
+
+            fn should_write(&self) -> bool {
+                !self.is_empty()
+            }
+
+            fn epee_default_value() -> Option<Self> {
+                Some($set::new())
+            }
+
+            fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+                write_varint(self.len().try_into()?, w)?;
+                for item in self.iter() {
+                    item.write(w)?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+// Sets are encoded the same as a `Vec` of their items, when reading duplicate items
+// are collapsed into one. A `BTreeSet` is written in sorted order so its encoding
+// is deterministic.
+#[cfg(feature = "std")]
+epee_set!(HashSet, core::hash::Hash + Eq);
+epee_set!(BTreeSet, Ord);
+
+/// A `SmallVec` is encoded the same as a `Vec` of its items.
+#[cfg(feature = "smallvec")]
+#[sealed]
+impl<A: smallvec::Array> EpeeValue for smallvec::SmallVec<A>
+where
+    A::Item: EpeeValue,
+    Vec<A::Item>: EpeeValue,
+{
+    const MARKER: Marker = Vec::<A::Item>::MARKER;
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        Ok(smallvec::SmallVec::from_vec(Vec::<A::Item>::read(
+            r, marker,
+        )?))
+    }
+
+    fn should_write(&self) -> bool {
+        !self.is_empty()
+    }
+
+    fn epee_default_value() -> Option<Self> {
+        Some(smallvec::SmallVec::new())
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_varint(self.len().try_into()?, w)?;
+        for item in self.iter() {
+            item.write(w)?;
+        }
+        Ok(())
+    }
+}
+
+/// An `ArrayVec` is encoded the same as a `Vec` of its items, when reading a sequence
+/// longer than the `ArrayVec`'s capacity returns an error before any items are read.
+#[cfg(feature = "arrayvec")]
+#[sealed]
+impl<T: EpeeValue, const CAP: usize> EpeeValue for arrayvec::ArrayVec<T, CAP>
+where
+    Vec<T>: EpeeValue,
+{
+    const MARKER: Marker = Vec::<T>::MARKER;
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        if marker != &Self::MARKER {
+            return Err(Error::UnexpectedMarker {
+                expected: Self::MARKER,
+                found: marker.clone(),
+            });
+        }
+
+        // Byte strings and sequences both start with their length, the bytes of
+        // a byte string are read as `u8`s.
+        let len = read_varint(r)?;
+        if len > CAP.try_into()? {
+            return Err(Error::Value(
+                "Sequence is longer than the ArrayVec's capacity",
+            ));
+        }
+        if marker.is_seq {
+            r.add_seq_elements(len)?;
+        }
+
+        let mut res = arrayvec::ArrayVec::new();
+        for _ in 0..len {
+            res.push(T::read(r, &T::MARKER)?);
+        }
+        Ok(res)
+    }
+
+    fn should_write(&self) -> bool {
+        !self.is_empty()
+    }
+
+    fn epee_default_value() -> Option<Self> {
+        Some(arrayvec::ArrayVec::new())
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_varint(self.len().try_into()?, w)?;
+        for item in self.iter() {
+            item.write(w)?;
+        }
+        Ok(())
+    }
+}
+
+// Shared pointers are encoded the same as the value they point to, when reading
+// a new pointer is created for every value.
+macro_rules! epee_shared_ptr {
+    ($ptr:ident) => {
+        #[sealed]
+        impl<T: EpeeValue> EpeeValue for $ptr<T> {
+            const MARKER: Marker = T::MARKER;
+
+            fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+                Ok($ptr::new(T::read(r, marker)?))
+            }
+
+            fn should_write(&self) -> bool {
+                self.as_ref().should_write()
+            }
+
+            fn epee_default_value() -> Option<Self> {
+                T::epee_default_value().map($ptr::new)
+            }
+
+            fn marker(&self) -> Marker {
+                self.as_ref().marker()
+            }
+
+            fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+                self.as_ref().write(w)
+            }
+        }
+    };
+}
+
+epee_shared_ptr!(Rc);
+#[cfg(target_has_atomic = "ptr")]
+epee_shared_ptr!(Arc);
+
+/// A `Cell<T>` is encoded the same as `T`, the value is copied out when writing.
+#[sealed]
+impl<T: EpeeValue + Copy> EpeeValue for Cell<T> {
+    const MARKER: Marker = T::MARKER;
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        Ok(Cell::new(T::read(r, marker)?))
+    }
+
+    fn should_write(&self) -> bool {
+        self.get().should_write()
+    }
+
+    fn epee_default_value() -> Option<Self> {
+        T::epee_default_value().map(Cell::new)
+    }
+
+    fn marker(&self) -> Marker {
+        self.get().marker()
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        self.get().write(w)
+    }
+}
+
+/// A `RefCell<T>` is encoded the same as `T`, writing returns an error if the value is
+/// mutably borrowed.
+#[sealed]
+impl<T: EpeeValue> EpeeValue for RefCell<T> {
+    const MARKER: Marker = T::MARKER;
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        Ok(RefCell::new(T::read(r, marker)?))
+    }
+
+    fn should_write(&self) -> bool {
+        // If the value is borrowed `write` will return the error.
+        self.try_borrow().map_or(true, |val| val.should_write())
+    }
+
+    fn epee_default_value() -> Option<Self> {
+        T::epee_default_value().map(RefCell::new)
+    }
+
+    fn marker(&self) -> Marker {
+        self.try_borrow().map_or(T::MARKER, |val| val.marker())
+    }
+
+    fn read_into<R: Read>(&mut self, r: &mut R, marker: &Marker) -> Result<()> {
+        self.get_mut().read_into(r, marker)
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        self.try_borrow()
+            .map_err(|_| Error::Value("RefCell is mutably borrowed"))?
+            .write(w)
+    }
+}
+
+/// A `Wrapping<T>` is encoded the same as `T`.
+#[sealed]
+impl<T: EpeeValue> EpeeValue for Wrapping<T> {
+    const MARKER: Marker = T::MARKER;
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        Ok(Wrapping(T::read(r, marker)?))
+    }
+
+    fn should_write(&self) -> bool {
+        self.0.should_write()
+    }
+
+    fn epee_default_value() -> Option<Self> {
+        T::epee_default_value().map(Wrapping)
+    }
+
+    fn marker(&self) -> Marker {
+        self.0.marker()
+    }
+
+    fn read_into<R: Read>(&mut self, r: &mut R, marker: &Marker) -> Result<()> {
+        self.0.read_into(r, marker)
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        self.0.write(w)
+    }
+}
+
+/// A `PhantomData` is never written, if a field with its name is in the data the
+/// value is skipped.
+#[sealed]
+impl<T: ?Sized> EpeeValue for PhantomData<T> {
+    const MARKER: Marker = Marker::new(InnerMarker::Object);
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        crate::skip_epee_value_with_marker(r, marker, &mut 0)?;
+        Ok(PhantomData)
+    }
+
+    fn should_write(&self) -> bool {
+        false
+    }
+
+    fn epee_default_value() -> Option<Self> {
+        Some(PhantomData)
+    }
+
+    fn write<W: Write>(&self, _w: &mut W) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A `Box<T>` is encoded exactly the same as `T`, this allows recursive types
+/// such as `struct Node { next: Option<Box<Node>> }`.
+///
+/// Only boxed [`EpeeObject`]s are supported as `Box` can't be given a generic [`EpeeValue`]
+/// impl next to the impl for all [`EpeeObject`]s.
+///
+/// When decoding with [`from_bytes`](crate::from_bytes) the depth of nested objects is
+/// limited, so deeply nested recursive types will return an error instead of overflowing
+/// the stack.
+impl<T: EpeeObject> EpeeObject for Box<T> {
+    type Builder = BoxBuilder<T>;
+}
+
+impl<T: EpeeObjectWrite> EpeeObjectWrite for Box<T> {
+    fn number_of_fields(&self) -> u64 {
+        self.as_ref().number_of_fields()
+    }
+
+    fn write_fields<W: Write>(&self, w: &mut W) -> Result<()> {
+        self.as_ref().write_fields(w)
+    }
+
+    fn write_object<W: Write>(&self, w: &mut W) -> Result<()> {
+        self.as_ref().write_object(w)
+    }
+
+    fn size_hint(&self) -> usize {
+        self.as_ref().size_hint()
+    }
+}
+
+/// The builder for a `Box<T>`, this just forwards to `T`'s builder.
+pub struct BoxBuilder<T: EpeeObject>(T::Builder);
+
+impl<T: EpeeObject> Default for BoxBuilder<T> {
+    fn default() -> Self {
+        BoxBuilder(T::Builder::default())
+    }
+}
+
+impl<T: EpeeObject> EpeeObjectBuilder<Box<T>> for BoxBuilder<T> {
+    fn add_field<R: Read>(&mut self, name: &str, r: &mut R) -> Result<bool> {
+        self.0.add_field(name, r)
+    }
+
+    fn finish(self) -> Result<Box<T>> {
+        Ok(Box::new(self.0.finish()?))
+    }
+}
+
+/// The marker of a [`Value`] is only known at runtime so [`EpeeValue::MARKER`] is
+/// not used, the marker is given by [`EpeeValue::marker`].
+#[sealed]
+impl EpeeValue for Value {
+    const MARKER: Marker = Marker::new(InnerMarker::Object);
+
+    fn read<R: Read>(r: &mut R, marker: &Marker) -> Result<Self> {
+        if marker.is_seq {
+            let len = read_varint(r)?;
+            r.add_seq_elements(len)?;
+
+            let individual_marker = Marker::new(marker.inner_marker.clone());
+
+            let mut res = Vec::with_capacity(seq_capacity::<Value>(len)?);
+            for _ in 0..len {
+                res.push(Self::read(r, &individual_marker)?);
+            }
+            return Ok(Value::Seq(marker.inner_marker.clone(), res));
+        }
+
+        Ok(match marker.inner_marker {
+            InnerMarker::I64 => Value::I64(i64::read(r, marker)?),
+            InnerMarker::I32 => Value::I32(i32::read(r, marker)?),
+            InnerMarker::I16 => Value::I16(i16::read(r, marker)?),
+            InnerMarker::I8 => Value::I8(i8::read(r, marker)?),
+            InnerMarker::U64 => Value::U64(u64::read(r, marker)?),
+            InnerMarker::U32 => Value::U32(u32::read(r, marker)?),
+            InnerMarker::U16 => Value::U16(u16::read(r, marker)?),
+            InnerMarker::U8 => Value::U8(u8::read(r, marker)?),
+            InnerMarker::F64 => Value::F64(f64::read(r, marker)?),
+            InnerMarker::String => Value::String(Vec::<u8>::read(r, marker)?),
+            InnerMarker::Bool => Value::Bool(bool::read(r, marker)?),
+            InnerMarker::Object => {
+                if !r.is_limited() {
+                    return Self::read(&mut limited(r), marker);
+                }
+
+                r.enter_object()?;
+                let res = crate::dynamic::read_value_object(r);
+                r.exit_object();
+                Value::Object(res?)
+            }
+        })
+    }
+
+    fn marker(&self) -> Marker {
+        let inner_marker = match self {
+            Value::I64(_) => InnerMarker::I64,
+            Value::I32(_) => InnerMarker::I32,
+            Value::I16(_) => InnerMarker::I16,
+            Value::I8(_) => InnerMarker::I8,
+            Value::U64(_) => InnerMarker::U64,
+            Value::U32(_) => InnerMarker::U32,
+            Value::U16(_) => InnerMarker::U16,
+            Value::U8(_) => InnerMarker::U8,
+            Value::F64(_) => InnerMarker::F64,
+            Value::String(_) => InnerMarker::String,
+            Value::Bool(_) => InnerMarker::Bool,
+            Value::Object(_) => InnerMarker::Object,
+            Value::Seq(inner_marker, _) => {
+                return Marker {
+                    inner_marker: inner_marker.clone(),
+                    is_seq: true,
+                }
+            }
+        };
+        Marker::new(inner_marker)
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        match self {
+            Value::I64(val) => val.write(w),
+            Value::I32(val) => val.write(w),
+            Value::I16(val) => val.write(w),
+            Value::I8(val) => val.write(w),
+            Value::U64(val) => val.write(w),
+            Value::U32(val) => val.write(w),
+            Value::U16(val) => val.write(w),
+            Value::U8(val) => val.write(w),
+            Value::F64(val) => val.write(w),
+            Value::String(val) => val.write(w),
+            Value::Bool(val) => val.write(w),
+            Value::Object(fields) => {
+                write_varint(fields.len().try_into()?, w)?;
+                for (name, val) in fields.iter() {
+                    crate::write_field(val, name, w)?;
+                }
+                Ok(())
+            }
+            Value::Seq(inner_marker, values) => {
+                write_varint(values.len().try_into()?, w)?;
+                for val in values.iter() {
+                    if val.marker() != Marker::new(inner_marker.clone()) {
+                        return Err(Error::Value(
+                            "Sequence value does not match the sequence's marker",
+                        ));
+                    }
+                    val.write(w)?;
+                }
+                Ok(())
+            }
         }
-        Ok(())
     }
 }
@@ -1,3 +1,8 @@
+//! Epee varints, the same varints are used in levin headers.
+//!
+//! The lowest 2 bits of the first byte give the length of the varint (1, 2, 4 or 8 bytes)
+//! and the remaining bits hold the number in little endian, so the largest number that
+//! can be encoded is [`MAX_VARINT`].
 use crate::error::*;
 use crate::io::*;
 
@@ -6,6 +11,10 @@ const FITS_IN_ONE_BYTE: u64 = 2_u64.pow(8 - SIZE_OF_SIZE_MARKER) - 1;
 const FITS_IN_TWO_BYTES: u64 = 2_u64.pow(16 - SIZE_OF_SIZE_MARKER) - 1;
 const FITS_IN_FOUR_BYTES: u64 = 2_u64.pow(32 - SIZE_OF_SIZE_MARKER) - 1;
 
+/// The largest number that can be written as a varint.
+pub const MAX_VARINT: u64 = u64::MAX >> SIZE_OF_SIZE_MARKER;
+
+/// Reads a varint.
 pub fn read_varint<R: Read>(reader: &mut R) -> Result<u64> {
     let vi_start = read_byte(reader)?;
     let len = match vi_start & 0b11 {
@@ -22,7 +31,30 @@ pub fn read_varint<R: Read>(reader: &mut R) -> Result<u64> {
     Ok(vi)
 }
 
+/// Reads a varint, erroring before the value is used if it is more than `max`. This
+/// should be used for lengths read from the data so they can't cause large allocations.
+pub fn read_varint_bounded<R: Read>(reader: &mut R, max: u64) -> Result<u64> {
+    let vi = read_varint(reader)?;
+    if vi > max {
+        return Err(Error::Format("Length exceeded maximum"));
+    }
+    Ok(vi)
+}
+
+/// Converts a length read from the data to a `usize`, returning [`Error::LengthTooLarge`]
+/// if it doesn't fit on this platform.
+pub(crate) fn len_to_usize(len: u64) -> Result<usize> {
+    usize::try_from(len).map_err(|_| Error::LengthTooLarge { len })
+}
+
+/// Writes a varint, returning an error if `number` is more than [`MAX_VARINT`].
 pub fn write_varint<W: Write>(number: u64, writer: &mut W) -> Result<()> {
+    if number > MAX_VARINT {
+        return Err(Error::Value(
+            "Number is too large to be written as a varint",
+        ));
+    }
+
     let size_marker = match number {
         0..=FITS_IN_ONE_BYTE => 0,
         64..=FITS_IN_TWO_BYTES => 1,
@@ -67,6 +99,8 @@ mod tests {
         assert_varint_length(FITS_IN_TWO_BYTES + 1, 4);
         assert_varint_length(FITS_IN_FOUR_BYTES, 4);
         assert_varint_length(FITS_IN_FOUR_BYTES + 1, 8);
+        assert_varint_length(MAX_VARINT, 8);
+        assert!(write_varint(MAX_VARINT + 1, &mut Vec::new()).is_err());
     }
 
     #[test]
@@ -77,5 +111,40 @@ mod tests {
         assert_varint_val(&[2, 0, 1, 0], FITS_IN_TWO_BYTES + 1);
         assert_varint_val(&[254, 255, 255, 255], FITS_IN_FOUR_BYTES);
         assert_varint_val(&[3, 0, 0, 0, 1, 0, 0, 0], FITS_IN_FOUR_BYTES + 1);
+        assert_varint_val(&[255; 8], MAX_VARINT);
+    }
+
+    #[test]
+    fn varint_read_bounded() {
+        let mut varint: &[u8] = &[2, 0, 1, 0];
+        assert_eq!(
+            read_varint_bounded(&mut varint, FITS_IN_TWO_BYTES + 1).unwrap(),
+            FITS_IN_TWO_BYTES + 1
+        );
+
+        let mut varint: &[u8] = &[2, 0, 1, 0];
+        assert!(read_varint_bounded(&mut varint, FITS_IN_TWO_BYTES).is_err());
+
+        // The max varint is rejected before it can be used as a length.
+        let mut varint: &[u8] = &[255; 8];
+        assert!(read_varint_bounded(&mut varint, 100).is_err());
+    }
+
+    #[test]
+    fn len_to_usize_platform() {
+        assert_eq!(
+            len_to_usize(u64::from(u32::MAX)).unwrap() as u64,
+            u64::from(u32::MAX)
+        );
+
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(len_to_usize(MAX_VARINT).unwrap() as u64, MAX_VARINT);
+
+        // A valid varint length which can't be used on a 32 bit platform.
+        #[cfg(target_pointer_width = "32")]
+        assert!(matches!(
+            len_to_usize(u64::from(u32::MAX) + 1),
+            Err(Error::LengthTooLarge { len }) if len == u64::from(u32::MAX) + 1
+        ));
     }
 }
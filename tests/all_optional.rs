@@ -0,0 +1,69 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject)]
+struct Full {
+    height: u64,
+    status: String,
+    top_hash: [u8; 32],
+    untrusted: bool,
+    txs: Vec<u64>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+#[epee(all_optional)]
+struct Partial {
+    height: u64,
+    status: String,
+    untrusted: bool,
+    #[epee_default(7)]
+    credits: u64,
+    txs: Vec<u64>,
+}
+
+#[derive(EpeeObject)]
+struct OnlyHeight {
+    height: u64,
+}
+
+#[test]
+fn all_optional_missing_fields() {
+    let bytes = to_bytes(&OnlyHeight { height: 88 }).unwrap();
+
+    let val: Partial = from_bytes(&bytes).unwrap();
+
+    assert_eq!(
+        val,
+        Partial {
+            height: 88,
+            status: String::new(),
+            untrusted: false,
+            credits: 7,
+            txs: vec![],
+        }
+    );
+}
+
+#[test]
+fn all_optional_present_fields() {
+    let full = Full {
+        height: 3,
+        status: "OK".to_string(),
+        top_hash: [1; 32],
+        untrusted: true,
+        txs: vec![5, 6],
+    };
+    let bytes = to_bytes(&full).unwrap();
+
+    let val: Partial = from_bytes(&bytes).unwrap();
+
+    assert_eq!(
+        val,
+        Partial {
+            height: 3,
+            status: "OK".to_string(),
+            untrusted: true,
+            credits: 7,
+            txs: vec![5, 6],
+        }
+    );
+}
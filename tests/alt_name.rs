@@ -1,4 +1,4 @@
-use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, EpeeObjectWrite};
 
 #[derive(EpeeObject)]
 struct AltName {
@@ -24,3 +24,38 @@ fn epee_alt_name() {
 
     assert_eq!(bytes, bytes2);
 }
+
+const VAL_NAME: &str = "val2";
+
+mod names {
+    pub const D: &str = "d";
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct ConstName {
+    #[epee_alt_name(VAL_NAME)]
+    val: u8,
+    #[epee_alt_name(names::D)]
+    other: u64,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct ByteStrName {
+    #[epee_alt_name(b"val2")]
+    val: u8,
+    d: u64,
+}
+
+#[test]
+fn epee_alt_name_const_and_byte_str() {
+    let bytes = to_bytes(&AltName2 { val2: 40, d: 30 }).unwrap();
+
+    let val: ConstName = from_bytes(&bytes).unwrap();
+    assert_eq!(val, ConstName { val: 40, other: 30 });
+    assert_eq!(to_bytes(&val).unwrap(), bytes);
+    assert_eq!(val.size_hint(), bytes.len() - 9 - 1);
+
+    let val: ByteStrName = from_bytes(&bytes).unwrap();
+    assert_eq!(val, ByteStrName { val: 40, d: 30 });
+    assert_eq!(to_bytes(&val).unwrap(), bytes);
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "arrayvec")]
+
+use arrayvec::ArrayVec;
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Peer {
+    id: u64,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Bounded {
+    peers: ArrayVec<Peer, 2>,
+    vals: ArrayVec<u32, 4>,
+    blob: ArrayVec<u8, 8>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Unbounded {
+    peers: Vec<Peer>,
+    vals: Vec<u32>,
+    blob: Vec<u8>,
+}
+
+#[test]
+fn encoded_like_vec() {
+    let mut bounded = Bounded {
+        peers: ArrayVec::new(),
+        vals: [1, 2, 3, 4].into(),
+        blob: ArrayVec::new(),
+    };
+    bounded.peers.push(Peer { id: 1 });
+    bounded.peers.push(Peer { id: 2 });
+    bounded.blob.try_extend_from_slice(&[1, 2, 3]).unwrap();
+
+    let unbounded = Unbounded {
+        peers: vec![Peer { id: 1 }, Peer { id: 2 }],
+        vals: vec![1, 2, 3, 4],
+        blob: vec![1, 2, 3],
+    };
+
+    let bytes = to_bytes(&bounded).unwrap();
+    assert_eq!(bytes, to_bytes(&unbounded).unwrap());
+    assert_eq!(from_bytes::<Bounded>(&bytes).unwrap(), bounded);
+}
+
+#[test]
+fn over_capacity_errors() {
+    let seq = to_bytes(&Unbounded {
+        peers: vec![],
+        vals: vec![1, 2, 3, 4, 5],
+        blob: vec![],
+    })
+    .unwrap();
+    assert!(from_bytes::<Bounded>(&seq).is_err());
+
+    let blob = to_bytes(&Unbounded {
+        peers: vec![Peer { id: 1 }, Peer { id: 2 }, Peer { id: 3 }],
+        vals: vec![],
+        blob: vec![0; 9],
+    })
+    .unwrap();
+    assert!(from_bytes::<Bounded>(&blob).is_err());
+
+    let blob = to_bytes(&Unbounded {
+        peers: vec![],
+        vals: vec![],
+        blob: vec![0; 9],
+    })
+    .unwrap();
+    assert!(from_bytes::<Bounded>(&blob).is_err());
+}
+
+#[test]
+fn empty_not_written() {
+    let bounded = Bounded {
+        peers: ArrayVec::new(),
+        vals: ArrayVec::new(),
+        blob: ArrayVec::new(),
+    };
+
+    let bytes = to_bytes(&bounded).unwrap();
+    assert_eq!(bytes.len(), 10);
+    assert_eq!(from_bytes::<Bounded>(&bytes).unwrap(), bounded);
+}
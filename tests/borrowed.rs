@@ -0,0 +1,98 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, EpeeObjectWrite};
+
+#[derive(EpeeObject)]
+struct Msg<'a> {
+    data: &'a [u8],
+    name: &'a str,
+    height: u64,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Owned {
+    data: Vec<u8>,
+    name: String,
+    height: u64,
+}
+
+#[derive(EpeeObject)]
+struct Parent<'a> {
+    #[epee_flatten]
+    msg: Msg<'a>,
+    #[epee_default("")]
+    note: &'a str,
+}
+
+#[derive(EpeeObject)]
+struct Nested<'a> {
+    child: &'a Msg<'a>,
+    height: u64,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct OwnedNested {
+    child: Owned,
+    height: u64,
+}
+
+#[test]
+fn borrowed_fields_written_as_owned() {
+    let data = vec![1, 2, 3];
+    let name = "name".to_string();
+    let msg = Msg {
+        data: &data,
+        name: &name,
+        height: 5,
+    };
+    let owned = Owned {
+        data: data.clone(),
+        name: name.clone(),
+        height: 5,
+    };
+
+    let bytes = to_bytes(&msg).unwrap();
+    assert_eq!(bytes, to_bytes(&owned).unwrap());
+    assert_eq!(from_bytes::<Owned>(&bytes).unwrap(), owned);
+}
+
+#[test]
+fn borrowed_objects_can_be_flattened() {
+    let msg = Msg {
+        data: &[1],
+        name: "name",
+        height: 5,
+    };
+    let owned = Owned {
+        data: vec![1],
+        name: "name".to_string(),
+        height: 5,
+    };
+    let val = Parent { msg, note: "" };
+
+    assert_eq!(val.number_of_fields(), 3);
+    assert_eq!(to_bytes(&val).unwrap(), to_bytes(&owned).unwrap());
+}
+
+#[test]
+fn borrowed_objects_can_be_nested() {
+    let msg = Msg {
+        data: &[1, 2],
+        name: "name",
+        height: 5,
+    };
+    let val = Nested {
+        child: &msg,
+        height: 6,
+    };
+    let owned = OwnedNested {
+        child: Owned {
+            data: vec![1, 2],
+            name: "name".to_string(),
+            height: 5,
+        },
+        height: 6,
+    };
+
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(bytes, to_bytes(&owned).unwrap());
+    assert_eq!(from_bytes::<OwnedNested>(&bytes).unwrap(), owned);
+}
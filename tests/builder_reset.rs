@@ -0,0 +1,31 @@
+use epee_encoding::{EpeeObject, EpeeObjectBuilder};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct T {
+    val: u8,
+    #[epee_default(2)]
+    other: u8,
+}
+
+#[test]
+fn reset_clears_added_fields() {
+    let mut builder = <T as EpeeObject>::Builder::default();
+
+    let mut r: &[u8] = &[0x08, 5];
+    assert!(builder.add_field("val", &mut r).unwrap());
+    let mut r: &[u8] = &[0x08, 6];
+    assert!(builder.add_field("other", &mut r).unwrap());
+
+    builder.reset();
+    assert!(builder.finish().is_err());
+
+    let mut builder = <T as EpeeObject>::Builder::default();
+    let mut r: &[u8] = &[0x08, 5];
+    builder.add_field("val", &mut r).unwrap();
+    builder.reset();
+
+    // The field can be added again without a duplicate key error.
+    let mut r: &[u8] = &[0x08, 7];
+    builder.add_field("val", &mut r).unwrap();
+    assert_eq!(builder.finish().unwrap(), T { val: 7, other: 2 });
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "bytes")]
+
+use bytes::{Bytes, BytesMut};
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct WithBytes {
+    blob: Bytes,
+    buf: BytesMut,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct WithVec {
+    blob: Vec<u8>,
+    buf: Vec<u8>,
+}
+
+#[test]
+fn bytes_round_trip() {
+    let val = WithBytes {
+        blob: Bytes::from_static(&[1, 2, 3]),
+        buf: BytesMut::from(&[4, 5][..]),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(from_bytes::<WithBytes>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn bytes_encoded_like_vec() {
+    let val = WithBytes {
+        blob: Bytes::from_static(&[1, 2, 3]),
+        buf: BytesMut::new(),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(
+        bytes,
+        to_bytes(&WithVec {
+            blob: vec![1, 2, 3],
+            buf: vec![],
+        })
+        .unwrap()
+    );
+}
@@ -0,0 +1,125 @@
+use std::collections::{BTreeMap, HashMap};
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, EpeeObjectWrite, InnerMarker, Value};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Child {
+    a: u8,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Full {
+    val: u64,
+    name: String,
+    seq: Vec<u32>,
+    child: Child,
+    children: Vec<Child>,
+    flag: bool,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Proxy {
+    val: u64,
+    #[epee_catch_all]
+    other: HashMap<String, Value>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Flat {
+    a: u8,
+    b: u64,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct FlattenProxy {
+    #[epee_flatten]
+    child: Child,
+    #[epee_catch_all]
+    other: BTreeMap<String, Value>,
+}
+
+fn full() -> Full {
+    Full {
+        val: 1,
+        name: "name".to_string(),
+        seq: vec![1, 2, 3],
+        child: Child { a: 2 },
+        children: vec![Child { a: 3 }, Child { a: 4 }],
+        flag: true,
+    }
+}
+
+#[test]
+fn unknown_fields_are_stored() {
+    let bytes = to_bytes(&full()).unwrap();
+    let proxy: Proxy = from_bytes(&bytes).unwrap();
+
+    assert_eq!(proxy.val, 1);
+    assert_eq!(proxy.other.len(), 5);
+    assert_eq!(proxy.other["name"], Value::String(b"name".to_vec()));
+    assert_eq!(
+        proxy.other["seq"],
+        Value::Seq(
+            InnerMarker::U32,
+            vec![Value::U32(1), Value::U32(2), Value::U32(3)]
+        )
+    );
+    assert_eq!(proxy.other["flag"], Value::Bool(true));
+    assert_eq!(
+        proxy.other["child"],
+        Value::Object([("a".to_string(), Value::U8(2))].into())
+    );
+}
+
+#[test]
+fn stored_fields_are_rewritten() {
+    let bytes = to_bytes(&full()).unwrap();
+    let proxy: Proxy = from_bytes(&bytes).unwrap();
+
+    let bytes = to_bytes(&proxy).unwrap();
+    assert_eq!(from_bytes::<Full>(&bytes).unwrap(), full());
+    assert_eq!(from_bytes::<Proxy>(&bytes).unwrap(), proxy);
+}
+
+#[test]
+fn catch_all_after_flatten() {
+    let bytes = to_bytes(&Flat { a: 2, b: 3 }).unwrap();
+    let proxy: FlattenProxy = from_bytes(&bytes).unwrap();
+
+    assert_eq!(proxy.child, Child { a: 2 });
+    assert_eq!(proxy.other.len(), 1);
+    assert_eq!(proxy.other["b"], Value::U64(3));
+}
+
+#[test]
+fn mismatched_seq_errors() {
+    let proxy = Proxy {
+        val: 1,
+        other: [(
+            "seq".to_string(),
+            Value::Seq(InnerMarker::U32, vec![Value::U8(1)]),
+        )]
+        .into(),
+    };
+
+    assert!(to_bytes(&proxy).is_err());
+}
+
+#[test]
+fn stored_fields_with_known_names_are_not_written() {
+    let proxy = Proxy {
+        val: 1,
+        other: [
+            ("val".to_string(), Value::U64(2)),
+            ("name".to_string(), Value::String(b"name".to_vec())),
+        ]
+        .into(),
+    };
+
+    assert_eq!(proxy.number_of_fields(), 2);
+    let bytes = to_bytes(&proxy).unwrap();
+    let read: Proxy = from_bytes(&bytes).unwrap();
+    assert_eq!(read.val, 1);
+    assert_eq!(read.other.len(), 1);
+    assert_eq!(read.other["name"], Value::String(b"name".to_vec()));
+}
@@ -0,0 +1,61 @@
+use std::cell::{Cell, RefCell};
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Cells {
+    height: Cell<u64>,
+    ids: RefCell<Vec<u32>>,
+    name: RefCell<String>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Plain {
+    height: u64,
+    ids: Vec<u32>,
+    name: String,
+}
+
+#[test]
+fn cells_encoded_as_inner() {
+    let cells = Cells {
+        height: Cell::new(5),
+        ids: RefCell::new(vec![1, 2]),
+        name: RefCell::new("name".to_string()),
+    };
+    let bytes = to_bytes(&cells).unwrap();
+
+    assert_eq!(
+        bytes,
+        to_bytes(&Plain {
+            height: 5,
+            ids: vec![1, 2],
+            name: "name".to_string(),
+        })
+        .unwrap()
+    );
+    assert_eq!(from_bytes::<Cells>(&bytes).unwrap(), cells);
+}
+
+#[test]
+fn empty_ref_cell_seq_not_written() {
+    let cells = Cells {
+        height: Cell::new(5),
+        ids: RefCell::new(vec![]),
+        name: RefCell::new(String::new()),
+    };
+    let bytes = to_bytes(&cells).unwrap();
+    assert_eq!(from_bytes::<Cells>(&bytes).unwrap(), cells);
+}
+
+#[test]
+fn mutably_borrowed_ref_cell_errors() {
+    let cells = Cells {
+        height: Cell::new(5),
+        ids: RefCell::new(vec![1]),
+        name: RefCell::new(String::new()),
+    };
+
+    let _borrow = cells.name.borrow_mut();
+    assert!(to_bytes(&cells).is_err());
+}
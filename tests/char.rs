@@ -0,0 +1,35 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Char {
+    val: char,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct CodePoint {
+    val: u32,
+}
+
+#[test]
+fn char_round_trip() {
+    for val in ['a', 'é', '🦀'] {
+        let bytes = to_bytes(&Char { val }).unwrap();
+        let decoded: Char = from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.val, val);
+    }
+}
+
+#[test]
+fn char_encoded_as_u32() {
+    let bytes = to_bytes(&Char { val: '🦀' }).unwrap();
+    let decoded: CodePoint = from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.val, 0x1F980);
+}
+
+#[test]
+fn char_invalid_code_point() {
+    for val in [0xD800, 0x110000] {
+        let bytes = to_bytes(&CodePoint { val }).unwrap();
+        assert!(from_bytes::<Char>(&bytes).is_err());
+    }
+}
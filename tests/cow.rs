@@ -0,0 +1,51 @@
+use std::borrow::Cow;
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Borrowed {
+    name: Cow<'static, str>,
+    blob: Cow<'static, [u8]>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Owned {
+    name: String,
+    blob: Vec<u8>,
+}
+
+#[test]
+fn cow_encoded_as_string_and_bytes() {
+    let owned = Owned {
+        name: "node".to_string(),
+        blob: vec![1, 2, 3],
+    };
+
+    let borrowed = Borrowed {
+        name: Cow::Borrowed("node"),
+        blob: Cow::Borrowed(&[1, 2, 3]),
+    };
+    let bytes = to_bytes(&borrowed).unwrap();
+    assert_eq!(bytes, to_bytes(&owned).unwrap());
+
+    let owned_cow = Borrowed {
+        name: Cow::Owned(owned.name.clone()),
+        blob: Cow::Owned(owned.blob.clone()),
+    };
+    assert_eq!(to_bytes(&owned_cow).unwrap(), bytes);
+}
+
+#[test]
+fn cow_reads_owned() {
+    let bytes = to_bytes(&Owned {
+        name: "node".to_string(),
+        blob: vec![1, 2, 3],
+    })
+    .unwrap();
+
+    let val: Borrowed = from_bytes(&bytes).unwrap();
+    assert!(matches!(val.name, Cow::Owned(_)));
+    assert!(matches!(val.blob, Cow::Owned(_)));
+    assert_eq!(val.name, "node");
+    assert_eq!(val.blob.as_ref(), [1, 2, 3]);
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "derive-debug-builders")]
+
+use epee_encoding::{EpeeObject, EpeeObjectBuilder};
+
+#[derive(EpeeObject)]
+struct Base {
+    height: u64,
+}
+
+/// Doesn't implement `Debug`, the builders don't need their fields to.
+#[derive(EpeeObject)]
+struct NoDebug {
+    val: u8,
+}
+
+#[derive(EpeeObject)]
+struct Request {
+    #[epee_flatten]
+    base: Base,
+    name: String,
+    #[epee_default(5)]
+    count: u8,
+    no_debug: NoDebug,
+}
+
+#[derive(EpeeObject)]
+#[epee_tag("type")]
+enum Message {
+    Ping { id: u32 },
+}
+
+#[test]
+fn builder_debug() {
+    let mut builder = <Request as EpeeObject>::Builder::default();
+
+    // A string field `name` with the value "a".
+    let mut r: &[u8] = &[0x0A, 0x04, b'a'];
+    assert!(builder.add_field("name", &mut r).unwrap());
+
+    let debug = format!("{:?}", builder);
+    assert_eq!(
+        debug,
+        "__RequestEpeeBuilder { base: flattened, name: read, count: not read, no_debug: not read }"
+    );
+}
+
+#[test]
+fn enum_builder_debug() {
+    let mut builder = <Message as EpeeObject>::Builder::default();
+    assert_eq!(format!("{:?}", builder), "__EpeeNoTag");
+
+    // The tag of `Ping`.
+    let mut r: &[u8] = &[0x08, 0];
+    assert!(builder.add_field("type", &mut r).unwrap());
+    assert_eq!(
+        format!("{:?}", builder),
+        "Ping(__MessagePingEpeeBuilder { id: not read })"
+    );
+}
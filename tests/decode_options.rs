@@ -0,0 +1,209 @@
+use epee_encoding::{
+    from_bytes, from_bytes_with_options, read_epee_value, skip_epee_value, to_bytes, DecodeOptions,
+    EpeeObject, Error, Value,
+};
+
+#[derive(EpeeObject, Debug)]
+struct Nested {
+    val: u8,
+    nested: Option<Box<Nested>>,
+}
+
+#[derive(EpeeObject, Debug)]
+struct Outer {
+    val: u8,
+}
+
+fn nested(depth: u8) -> Nested {
+    let mut val = Nested {
+        val: 0,
+        nested: None,
+    };
+    for _ in 0..depth {
+        val = Nested {
+            val: 0,
+            nested: Some(Box::new(val)),
+        };
+    }
+    val
+}
+
+#[test]
+fn skipped_object_depth_limit() {
+    // `Outer` doesn't have the nested field so the objects will be skipped.
+    let bytes = to_bytes(&nested(5)).unwrap();
+
+    assert!(from_bytes::<Outer>(&bytes).is_ok());
+
+    let options = DecodeOptions::default().max_skipped_object_depth(5);
+    assert!(from_bytes_with_options::<Outer>(&bytes, &options).is_ok());
+
+    let options = DecodeOptions::default().max_skipped_object_depth(4);
+    let err = from_bytes_with_options::<Outer>(&bytes, &options).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+}
+
+#[test]
+fn max_skipped_object_depth_limit() {
+    let options = DecodeOptions::default()
+        .max_skipped_object_depth(255)
+        .max_object_depth(255);
+
+    let bytes = to_bytes(&nested(255)).unwrap();
+    assert!(from_bytes_with_options::<Outer>(&bytes, &options).is_ok());
+
+    let val = Nested {
+        val: 0,
+        nested: Some(Box::new(nested(255))),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    let err = from_bytes_with_options::<Outer>(&bytes, &options).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+}
+
+#[test]
+fn object_depth_limit() {
+    let bytes = to_bytes(&nested(5)).unwrap();
+
+    let options = DecodeOptions::default().max_object_depth(5);
+    assert!(from_bytes_with_options::<Nested>(&bytes, &options).is_ok());
+
+    let options = DecodeOptions::default().max_object_depth(4);
+    let err = from_bytes_with_options::<Nested>(&bytes, &options).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+}
+
+#[test]
+fn max_object_depth_limit() {
+    let bytes = to_bytes(&nested(255)).unwrap();
+
+    let options = DecodeOptions::default().max_object_depth(255);
+    assert!(from_bytes_with_options::<Nested>(&bytes, &options).is_ok());
+
+    let mut val = nested(255);
+    val = Nested {
+        val: 0,
+        nested: Some(Box::new(val)),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    let err = from_bytes_with_options::<Nested>(&bytes, &options).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+}
+
+/// `val` as an epee value, with a marker, without the header.
+fn value_bytes(val: &Nested) -> Vec<u8> {
+    let mut bytes = vec![0x0c];
+    bytes.extend_from_slice(&to_bytes(val).unwrap()[9..]);
+    bytes
+}
+
+#[test]
+fn plain_reader_depth_limit() {
+    // The object read by `read_epee_value` counts towards the depth.
+    let bytes = value_bytes(&nested(99));
+    assert!(read_epee_value::<Nested, _>(&mut bytes.as_slice()).is_ok());
+    assert!(read_epee_value::<Value, _>(&mut bytes.as_slice()).is_ok());
+
+    let bytes = value_bytes(&nested(100));
+    let err = read_epee_value::<Nested, _>(&mut bytes.as_slice()).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+    let err = read_epee_value::<Value, _>(&mut bytes.as_slice()).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+    // Skipped objects have a lower limit.
+    let err = skip_epee_value(&mut bytes.as_slice()).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+}
+
+#[test]
+fn object_count_limit() {
+    let bytes = to_bytes(&nested(5)).unwrap();
+
+    let options = DecodeOptions::default().max_objects(5);
+    assert!(from_bytes_with_options::<Outer>(&bytes, &options).is_ok());
+
+    let options = DecodeOptions::default().max_objects(4);
+    let err = from_bytes_with_options::<Outer>(&bytes, &options).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+}
+
+#[derive(EpeeObject, Debug)]
+struct Seqs {
+    a: Vec<u64>,
+    b: Vec<String>,
+    blob: Vec<u8>,
+}
+
+/// Skips every field.
+#[derive(EpeeObject, Debug)]
+struct SkipAll {}
+
+fn seqs() -> Seqs {
+    Seqs {
+        a: vec![1, 2, 3],
+        b: vec!["a".to_string(), "b".to_string()],
+        blob: vec![0; 10],
+    }
+}
+
+#[test]
+fn field_count_limit() {
+    let bytes = to_bytes(&seqs()).unwrap();
+
+    let options = DecodeOptions::default().max_fields(3);
+    assert!(from_bytes_with_options::<Seqs>(&bytes, &options).is_ok());
+
+    let options = DecodeOptions::default().max_fields(2);
+    let err = from_bytes_with_options::<Seqs>(&bytes, &options).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+}
+
+#[test]
+fn seq_elements_limit() {
+    let bytes = to_bytes(&seqs()).unwrap();
+
+    let options = DecodeOptions::default().max_seq_elements(5);
+    assert!(from_bytes_with_options::<Seqs>(&bytes, &options).is_ok());
+    // Skipped sequences count too.
+    assert!(from_bytes_with_options::<SkipAll>(&bytes, &options).is_ok());
+
+    let options = DecodeOptions::default().max_seq_elements(4);
+    let err = from_bytes_with_options::<Seqs>(&bytes, &options).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+    let err = from_bytes_with_options::<SkipAll>(&bytes, &options).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+}
+
+#[test]
+fn byte_array_len_limit() {
+    let bytes = to_bytes(&seqs()).unwrap();
+
+    let options = DecodeOptions::default().max_byte_array_len(10);
+    assert!(from_bytes_with_options::<Seqs>(&bytes, &options).is_ok());
+    assert!(from_bytes_with_options::<SkipAll>(&bytes, &options).is_ok());
+
+    let options = DecodeOptions::default().max_byte_array_len(9);
+    let err = from_bytes_with_options::<Seqs>(&bytes, &options).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+    let err = from_bytes_with_options::<SkipAll>(&bytes, &options).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+}
+
+/// The header followed by an object claiming to have `fields` fields, without any fields.
+fn claimed_fields(fields: u32) -> Vec<u8> {
+    let mut data = vec![0x01, 0x11, 0x01, 0x1, 0x01, 0x01, 0x02, 0x1, 0x1];
+    // A 4 byte varint.
+    data.extend_from_slice(&((fields << 2) | 2).to_le_bytes());
+    data
+}
+
+#[test]
+fn absurd_field_count_rejected_by_default() {
+    let err = from_bytes::<SkipAll>(&claimed_fields(1_000_000_000)).unwrap_err();
+    assert!(matches!(err.root(), Error::Format(_)));
+
+    // Under the limit the data just runs out.
+    let err = from_bytes::<SkipAll>(&claimed_fields(1000)).unwrap_err();
+    assert!(matches!(err.root(), Error::IO(_)));
+}
@@ -30,3 +30,55 @@ fn duplicate_key_with_default() {
 
     assert!(from_bytes::<TT>(&data).is_err());
 }
+
+#[derive(EpeeObject)]
+struct Parent {
+    #[epee_flatten]
+    child: T,
+    b: u8,
+}
+
+#[derive(EpeeObject)]
+struct OptionalKey {
+    a: Option<bool>,
+}
+
+#[derive(EpeeObject)]
+struct Nested {
+    n: T,
+}
+
+#[test]
+fn duplicate_key_in_flattened_child() {
+    let data = [
+        0x01, 0x11, 0x01, 0x1, 0x01, 0x01, 0x02, 0x1, 0x1, 0x0C, 0x01, b'a', 0x08, 0x00, 0x01,
+        b'b', 0x08, 0x01, 0x01, b'a', 0x08, 0x01,
+    ];
+
+    let err = from_bytes::<Parent>(&data).err().unwrap();
+    assert_eq!(err.root().to_string(), "Format error: Double key in data!");
+}
+
+#[test]
+fn duplicate_key_optional() {
+    let data = [
+        0x01, 0x11, 0x01, 0x1, 0x01, 0x01, 0x02, 0x1, 0x1, 0x08, 0x01, b'a', 0x0B, 0x00, 0x01,
+        b'a', 0x0B, 0x00,
+    ];
+
+    assert!(from_bytes::<OptionalKey>(&data).is_err());
+}
+
+#[test]
+fn duplicate_key_in_nested_object() {
+    let data = [
+        0x01, 0x11, 0x01, 0x1, 0x01, 0x01, 0x02, 0x1, 0x1, 0x04, 0x01, b'n', 0x0C, 0x08, 0x01,
+        b'a', 0x08, 0x00, 0x01, b'a', 0x08, 0x00,
+    ];
+
+    let err = from_bytes::<Nested>(&data).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Error in field `n`: Error in field `a`: Format error: Double key in data!"
+    );
+}
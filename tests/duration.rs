@@ -0,0 +1,32 @@
+use core::time::Duration;
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Timeout {
+    timeout: Duration,
+}
+
+#[derive(EpeeObject)]
+struct Secs {
+    timeout: u64,
+}
+
+#[test]
+fn duration_round_trip() {
+    let val = Timeout {
+        timeout: Duration::from_secs(90),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(bytes, to_bytes(&Secs { timeout: 90 }).unwrap());
+    assert_eq!(from_bytes::<Timeout>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn fractional_duration_errors() {
+    let val = Timeout {
+        timeout: Duration::from_millis(1500),
+    };
+    assert!(to_bytes(&val).is_err());
+}
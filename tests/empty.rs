@@ -0,0 +1,44 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct UnitEmpty;
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct BracedEmpty {}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Request {
+    empty: BracedEmpty,
+    unit: UnitEmpty,
+}
+
+#[derive(EpeeObject)]
+struct Extra {
+    val: u64,
+}
+
+#[test]
+fn empty_structs() {
+    let bytes = [0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x00];
+
+    assert_eq!(to_bytes(&UnitEmpty).unwrap(), bytes);
+    assert_eq!(to_bytes(&BracedEmpty {}).unwrap(), bytes);
+    assert_eq!(from_bytes::<UnitEmpty>(&bytes).unwrap(), UnitEmpty);
+    assert_eq!(from_bytes::<BracedEmpty>(&bytes).unwrap(), BracedEmpty {});
+}
+
+#[test]
+fn empty_struct_fields() {
+    let val = Request {
+        empty: BracedEmpty {},
+        unit: UnitEmpty,
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<Request>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn empty_struct_ignores_unknown_fields() {
+    let bytes = to_bytes(&Extra { val: 5 }).unwrap();
+    assert_eq!(from_bytes::<UnitEmpty>(&bytes).unwrap(), UnitEmpty);
+}
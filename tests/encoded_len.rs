@@ -0,0 +1,66 @@
+use epee_encoding::io::{ByteCounter, Write};
+use epee_encoding::{encoded_len, to_bytes, to_bytes_no_header, EpeeObject, EpeeValue};
+
+#[derive(EpeeObject)]
+struct Child {
+    val: u8,
+    name: String,
+}
+
+#[derive(EpeeObject)]
+struct Message {
+    height: u64,
+    blob: Vec<u8>,
+    children: Vec<Child>,
+    #[epee_default(5)]
+    count: u32,
+}
+
+#[test]
+fn encoded_len_matches_to_bytes() {
+    let vals = [
+        Message {
+            height: 1,
+            blob: vec![],
+            children: vec![],
+            count: 5,
+        },
+        Message {
+            height: 1000,
+            blob: vec![1; 300],
+            children: vec![
+                Child {
+                    val: 1,
+                    name: "a".repeat(100),
+                },
+                Child {
+                    val: 2,
+                    name: String::new(),
+                },
+            ],
+            count: 6,
+        },
+    ];
+
+    for val in vals.iter() {
+        assert_eq!(encoded_len(val).unwrap(), to_bytes(val).unwrap().len());
+    }
+}
+
+#[test]
+fn byte_counter_counts_writes() {
+    let val = Child {
+        val: 1,
+        name: "name".to_string(),
+    };
+
+    let mut counter = ByteCounter::new();
+    val.write(&mut counter).unwrap();
+    assert_eq!(counter.count(), to_bytes_no_header(&val).unwrap().len());
+
+    counter.write_all(&[0; 10]).unwrap();
+    assert_eq!(
+        counter.count(),
+        to_bytes_no_header(&val).unwrap().len() + 10
+    );
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, EpeeObjectWrite, Value};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+#[epee_tag("type")]
+enum Msg {
+    Ping,
+    Data {
+        height: u64,
+        #[epee_alt_name("blob")]
+        data: Vec<u8>,
+    },
+    Status {
+        #[epee_default(0)]
+        code: u8,
+        msg: String,
+    },
+    Pair(u64, String),
+}
+
+#[derive(EpeeObject, Debug, PartialEq, Clone, Copy)]
+#[epee_tag("state")]
+enum State {
+    Idle,
+    Syncing = 4,
+    Synced,
+    Failed = 255,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+#[epee_tag("type")]
+enum Proxied {
+    Data {
+        height: u64,
+        #[epee_catch_all]
+        other: BTreeMap<String, Value>,
+    },
+}
+
+#[derive(EpeeObject)]
+struct StateTag {
+    state: u8,
+}
+
+#[derive(EpeeObject)]
+struct Tagged {
+    r#type: u8,
+    height: u64,
+    blob: Vec<u8>,
+}
+
+#[derive(EpeeObject)]
+struct TagLast {
+    height: u64,
+    blob: Vec<u8>,
+    r#type: u8,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct PairFields {
+    r#type: u8,
+    #[epee_alt_name("0")]
+    zero: u64,
+    #[epee_alt_name("1")]
+    one: String,
+}
+
+#[derive(EpeeObject)]
+struct Extra {
+    extra: Vec<u64>,
+    r#type: u8,
+}
+
+#[derive(EpeeObject)]
+struct DoubleHeight {
+    height: u64,
+    r#type: u8,
+    #[epee_alt_name("height")]
+    other: u64,
+}
+
+#[test]
+fn enum_round_trip() {
+    for msg in [
+        Msg::Ping,
+        Msg::Data {
+            height: 5,
+            data: vec![1, 2, 3],
+        },
+        Msg::Status {
+            code: 0,
+            msg: "OK".to_string(),
+        },
+        Msg::Status {
+            code: 4,
+            msg: "BUSY".to_string(),
+        },
+        Msg::Pair(7, "seven".to_string()),
+    ] {
+        let bytes = to_bytes(&msg).unwrap();
+        assert_eq!(msg, from_bytes(&bytes).unwrap());
+    }
+}
+
+#[test]
+fn enum_tag_is_written_first() {
+    let bytes = to_bytes(&Msg::Ping).unwrap();
+    assert_eq!(&bytes[9..], [0x04, 0x04, b't', b'y', b'p', b'e', 0x08, 0]);
+
+    let bytes = to_bytes(&Tagged {
+        r#type: 1,
+        height: 9,
+        blob: vec![0xff],
+    })
+    .unwrap();
+    assert_eq!(
+        from_bytes::<Msg>(&bytes).unwrap(),
+        Msg::Data {
+            height: 9,
+            data: vec![0xff]
+        }
+    );
+}
+
+#[test]
+fn enum_unknown_tag() {
+    let bytes = to_bytes(&Tagged {
+        r#type: 3,
+        height: 9,
+        blob: vec![],
+    })
+    .unwrap();
+    assert!(from_bytes::<Msg>(&bytes).is_err());
+}
+
+#[test]
+fn enum_tag_not_first() {
+    let bytes = to_bytes(&TagLast {
+        r#type: 1,
+        height: 9,
+        blob: vec![1],
+    })
+    .unwrap();
+    assert_eq!(
+        from_bytes::<Msg>(&bytes).unwrap(),
+        Msg::Data {
+            height: 9,
+            data: vec![1]
+        }
+    );
+}
+
+#[test]
+fn enum_unknown_fields_before_tag() {
+    let bytes = to_bytes(&Extra {
+        extra: vec![1, 2],
+        r#type: 0,
+    })
+    .unwrap();
+    assert_eq!(from_bytes::<Msg>(&bytes).unwrap(), Msg::Ping);
+
+    // Fields before the tag are still checked for duplicates.
+    let bytes = to_bytes(&DoubleHeight {
+        height: 1,
+        r#type: 1,
+        other: 2,
+    })
+    .unwrap();
+    assert!(from_bytes::<Msg>(&bytes).is_err());
+}
+
+#[test]
+fn enum_unnamed_fields_named_by_index() {
+    let msg = Msg::Pair(7, "seven".to_string());
+    assert_eq!(msg.number_of_fields(), 3);
+
+    let bytes = to_bytes(&msg).unwrap();
+    assert_eq!(
+        from_bytes::<PairFields>(&bytes).unwrap(),
+        PairFields {
+            r#type: 3,
+            zero: 7,
+            one: "seven".to_string(),
+        }
+    );
+}
+
+#[test]
+fn enum_discriminants_are_tags() {
+    for (state, tag) in [
+        (State::Idle, 0),
+        (State::Syncing, 4),
+        (State::Synced, 5),
+        (State::Failed, 255),
+    ] {
+        let bytes = to_bytes(&state).unwrap();
+        assert_eq!(bytes, to_bytes(&StateTag { state: tag }).unwrap());
+        assert_eq!(from_bytes::<State>(&bytes).unwrap(), state);
+    }
+
+    let bytes = to_bytes(&StateTag { state: 1 }).unwrap();
+    assert!(from_bytes::<State>(&bytes).is_err());
+}
+
+#[test]
+fn enum_catch_all_does_not_write_tag() {
+    let msg = Proxied::Data {
+        height: 1,
+        other: [("type".to_string(), Value::U8(3))].into(),
+    };
+
+    assert_eq!(msg.number_of_fields(), 2);
+    let bytes = to_bytes(&msg).unwrap();
+    assert_eq!(
+        from_bytes::<Proxied>(&bytes).unwrap(),
+        Proxied::Data {
+            height: 1,
+            other: BTreeMap::new(),
+        }
+    );
+}
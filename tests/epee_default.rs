@@ -1,4 +1,4 @@
-use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, EpeeObjectWrite};
 
 #[derive(EpeeObject)]
 pub struct Optional {
@@ -59,3 +59,176 @@ fn epee_value_not_present_with_default() {
     assert_eq!(val.optional_val, -4);
     assert_eq!(val.val, 76)
 }
+
+#[derive(EpeeObject, Debug, PartialEq)]
+pub struct WithSeq {
+    val: u8,
+    seq: Vec<u64>,
+    opt: Option<u32>,
+}
+
+#[test]
+fn empty_seq_is_not_encoded_but_decodes() {
+    let val = WithSeq {
+        val: 1,
+        seq: vec![],
+        opt: None,
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(from_bytes::<WithSeq>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn missing_fields_use_type_default_value() {
+    let bytes = to_bytes(&NotPresent { val: 3 }).unwrap();
+
+    let val: WithSeq = from_bytes(&bytes).unwrap();
+    assert_eq!(val.val, 3);
+    assert!(val.seq.is_empty());
+    assert!(val.opt.is_none());
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+pub struct BareDefault {
+    val: u8,
+    #[epee_default]
+    count: u64,
+    #[epee_default]
+    name: String,
+}
+
+#[test]
+fn bare_epee_default_uses_default_impl() {
+    let val = BareDefault {
+        val: 1,
+        count: 0,
+        name: String::new(),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    // Only `val` is encoded as the other fields are their default.
+    assert_eq!(bytes, to_bytes(&NotPresent { val: 1 }).unwrap());
+    assert_eq!(from_bytes::<BareDefault>(&bytes).unwrap(), val);
+
+    let val = BareDefault {
+        val: 1,
+        count: 5,
+        name: "a".to_string(),
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<BareDefault>(&bytes).unwrap(), val);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+pub struct GenericBareDefault<T> {
+    #[epee_default]
+    val: T,
+}
+
+#[test]
+fn bare_epee_default_generic() {
+    let bytes = to_bytes(&GenericBareDefault { val: 0_u32 }).unwrap();
+    assert_eq!(bytes, to_bytes(&GenericBareDefault { val: 0_u8 }).unwrap());
+
+    let val: GenericBareDefault<u32> = from_bytes(&bytes).unwrap();
+    assert_eq!(val.val, 0);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+pub struct SubObject {
+    a: u8,
+    b: String,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+pub struct OptionalSubObject {
+    val: u8,
+    sub: Option<SubObject>,
+}
+
+#[test]
+fn absent_optional_sub_object() {
+    // The sub-object key is not in the data.
+    let bytes = to_bytes(&NotPresent { val: 4 }).unwrap();
+    let val: OptionalSubObject = from_bytes(&bytes).unwrap();
+    assert_eq!(val, OptionalSubObject { val: 4, sub: None });
+
+    // `None` is not written so it round trips.
+    assert_eq!(to_bytes(&val).unwrap(), bytes);
+
+    let val = OptionalSubObject {
+        val: 4,
+        sub: Some(SubObject {
+            a: 1,
+            b: "b".to_string(),
+        }),
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<OptionalSubObject>(&bytes).unwrap(), val);
+}
+
+fn is_zero(val: &f64) -> bool {
+    *val == 0.0
+}
+
+fn is_nan(val: &f64) -> bool {
+    val.is_nan()
+}
+
+/// A type without `PartialEq`.
+#[derive(EpeeObject, Default)]
+pub struct NoEq {
+    a: u8,
+}
+
+fn no_eq_is_default(val: &NoEq) -> bool {
+    val.a == 0
+}
+
+#[derive(EpeeObject)]
+pub struct DefaultIf {
+    val: u8,
+    #[epee_default_if(is_zero)]
+    zero: f64,
+    #[epee_default_if(is_nan)]
+    #[epee_default(f64::NAN)]
+    nan: f64,
+    #[epee_default_if(no_eq_is_default)]
+    no_eq: NoEq,
+}
+
+#[test]
+fn epee_default_if_does_not_encode() {
+    let val = DefaultIf {
+        val: 1,
+        zero: -0.0,
+        nan: f64::NAN,
+        no_eq: NoEq { a: 0 },
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(bytes, to_bytes(&NotPresent { val: 1 }).unwrap());
+
+    let val: DefaultIf = from_bytes(&bytes).unwrap();
+    assert_eq!(val.val, 1);
+    assert_eq!(val.zero, 0.0);
+    assert!(val.nan.is_nan());
+    assert_eq!(val.no_eq.a, 0);
+}
+
+#[test]
+fn epee_default_if_non_default_does_encode() {
+    let val = DefaultIf {
+        val: 1,
+        zero: 2.5,
+        nan: 1.5,
+        no_eq: NoEq { a: 3 },
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(val.number_of_fields(), 4);
+
+    let val: DefaultIf = from_bytes(&bytes).unwrap();
+    assert_eq!(val.zero, 2.5);
+    assert_eq!(val.nan, 1.5);
+    assert_eq!(val.no_eq.a, 3);
+}
@@ -0,0 +1,109 @@
+use epee_encoding::{from_bytes, EpeeObject, Error, InnerMarker, Marker};
+
+#[derive(EpeeObject)]
+struct T {
+    val: u8,
+}
+
+#[test]
+fn error_display() {
+    assert_eq!(Error::IO("a").to_string(), "IO error: a");
+    assert_eq!(Error::Format("b").to_string(), "Format error: b");
+    assert_eq!(Error::Value("c").to_string(), "Value error: c");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn error_into_boxed_error() {
+    fn decode() -> Result<T, Box<dyn std::error::Error>> {
+        Ok(from_bytes(&[0; 9])?)
+    }
+
+    let err = decode().err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Format error: Expected header `01 11 01 01 01 01 02 01 01` but found `00 00 00 00 00 00 00 00 00`"
+    );
+}
+
+#[test]
+fn header_mismatch_has_found_bytes() {
+    let err = from_bytes::<T>(b"\x01\x11\x01\x01\x01\x01\x02\x01\x02\x00")
+        .err()
+        .unwrap();
+    match err {
+        Error::HeaderMismatch { found } => {
+            assert_eq!(found, *b"\x01\x11\x01\x01\x01\x01\x02\x01\x02")
+        }
+        _ => panic!("expected a header mismatch, got: {:?}", err),
+    }
+}
+
+#[test]
+fn error_from_decode() {
+    let err = from_bytes::<T>(b"\x01\x11\x01\x01\x01\x01\x02\x01\x01\x00")
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Format error: Required field `val` was not found!"
+    );
+}
+
+#[derive(EpeeObject)]
+struct Parent {
+    child: T,
+}
+
+#[derive(EpeeObject)]
+struct WrongChild {
+    val: u64,
+}
+
+#[derive(EpeeObject)]
+struct WrongParent {
+    child: WrongChild,
+}
+
+#[test]
+fn error_has_field_context() {
+    let bytes = epee_encoding::to_bytes(&WrongParent {
+        child: WrongChild { val: 1 },
+    })
+    .unwrap();
+
+    let err = from_bytes::<Parent>(&bytes).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Error in field `child`: Error in field `val`: Format error: Expected marker `u8` but found `u64`"
+    );
+    assert!(matches!(
+        err.root(),
+        Error::UnexpectedMarker {
+            expected: Marker {
+                inner_marker: InnerMarker::U8,
+                is_seq: false
+            },
+            found: Marker {
+                inner_marker: InnerMarker::U64,
+                is_seq: false
+            },
+        }
+    ));
+}
+
+#[derive(EpeeObject)]
+struct Seq {
+    val: Vec<u64>,
+}
+
+#[test]
+fn unexpected_marker_for_seq() {
+    let bytes = epee_encoding::to_bytes(&WrongChild { val: 1 }).unwrap();
+
+    let err = from_bytes::<Seq>(&bytes).err().unwrap();
+    assert_eq!(
+        err.root().to_string(),
+        "Format error: Expected marker `seq<u64>` but found `u64`"
+    );
+}
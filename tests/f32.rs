@@ -0,0 +1,92 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, EpeeObjectWrite};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Single {
+    val: f32,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Double {
+    val: f64,
+}
+
+#[test]
+fn f32_round_trip() {
+    for val in [
+        0.0,
+        -0.0,
+        1.5,
+        -3.25,
+        f32::MAX,
+        f32::MIN_POSITIVE,
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+    ] {
+        let bytes = to_bytes(&Single { val }).unwrap();
+        let decoded: Single = from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.val.to_bits(), val.to_bits());
+    }
+
+    let bytes = to_bytes(&Single { val: f32::NAN }).unwrap();
+    assert!(from_bytes::<Single>(&bytes).unwrap().val.is_nan());
+}
+
+#[test]
+fn f32_encoded_as_f64() {
+    let bytes = to_bytes(&Single { val: 0.1 }).unwrap();
+    let decoded: Double = from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.val, f64::from(0.1_f32));
+}
+
+#[test]
+fn f32_precision_loss() {
+    let bytes = to_bytes(&Double { val: 0.1 }).unwrap();
+    let decoded: Single = from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.val, 0.1_f32);
+}
+
+#[test]
+fn f32_too_large() {
+    let bytes = to_bytes(&Double { val: f64::MAX }).unwrap();
+    assert!(from_bytes::<Single>(&bytes).is_err());
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct SingleSeq {
+    seq: Vec<f32>,
+    arr: [f32; 3],
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct DoubleSeq {
+    seq: Vec<f64>,
+    arr: [f64; 3],
+}
+
+#[test]
+fn f32_seq_round_trip() {
+    let val = SingleSeq {
+        seq: vec![0.5, -1.25, f32::MAX, f32::INFINITY],
+        arr: [1.0, 2.0, 0.1],
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<SingleSeq>(&bytes).unwrap(), val);
+
+    let decoded: DoubleSeq = from_bytes(&bytes).unwrap();
+    assert_eq!(
+        decoded.seq,
+        [0.5, -1.25, f64::from(f32::MAX), f64::INFINITY]
+    );
+    assert_eq!(decoded.arr, [1.0, 2.0, f64::from(0.1_f32)]);
+}
+
+#[test]
+fn empty_f32_seq_not_written() {
+    let val = SingleSeq {
+        seq: vec![],
+        arr: [0.0; 3],
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(val.number_of_fields(), 1);
+    assert_eq!(from_bytes::<SingleSeq>(&bytes).unwrap(), val);
+}
@@ -0,0 +1,60 @@
+use epee_encoding::{from_bytes, from_bytes_with_options, to_bytes, DecodeOptions, EpeeObject};
+
+#[derive(EpeeObject, Debug)]
+struct Double {
+    val: f64,
+    vals: Vec<f64>,
+}
+
+fn round_trip(val: f64, options: &DecodeOptions) -> f64 {
+    let bytes = to_bytes(&Double {
+        val,
+        vals: vec![val],
+    })
+    .unwrap();
+    let decoded: Double = from_bytes_with_options(&bytes, options).unwrap();
+    assert_eq!(decoded.val.to_bits(), decoded.vals[0].to_bits());
+    decoded.val
+}
+
+#[test]
+fn zeros_keep_their_sign() {
+    let options = DecodeOptions::default();
+    assert_eq!(round_trip(0.0, &options).to_bits(), 0.0_f64.to_bits());
+    assert_eq!(round_trip(-0.0, &options).to_bits(), (-0.0_f64).to_bits());
+
+    // Both zeros are written.
+    let bytes = to_bytes(&Double {
+        val: -0.0,
+        vals: vec![],
+    })
+    .unwrap();
+    assert!(from_bytes::<Double>(&bytes).unwrap().val.is_sign_negative());
+}
+
+#[test]
+fn nan_bits_kept_by_default() {
+    let nan = f64::from_bits(f64::NAN.to_bits() | 1);
+    assert!(nan.is_nan());
+
+    let decoded = round_trip(nan, &DecodeOptions::default());
+    assert_eq!(decoded.to_bits(), nan.to_bits());
+}
+
+#[test]
+fn nan_canonicalized() {
+    let options = DecodeOptions::default().canonicalize_nan(true);
+
+    let nan = f64::from_bits(f64::NAN.to_bits() | 1);
+    let decoded = round_trip(nan, &options);
+    assert_eq!(decoded.to_bits(), f64::NAN.to_bits());
+
+    let nan = -f64::NAN;
+    let decoded = round_trip(nan, &options);
+    assert_eq!(decoded.to_bits(), f64::NAN.to_bits());
+
+    // Other values are not changed.
+    assert_eq!(round_trip(-0.0, &options).to_bits(), (-0.0_f64).to_bits());
+    assert_eq!(round_trip(f64::INFINITY, &options), f64::INFINITY);
+    assert_eq!(round_trip(f64::NEG_INFINITY, &options), f64::NEG_INFINITY);
+}
@@ -0,0 +1,66 @@
+use epee_encoding::varint::{read_varint, write_varint};
+use epee_encoding::{
+    from_bytes, read_epee_value, read_field_name_bytes, to_bytes, write_field_bytes, EpeeObject,
+    Error,
+};
+
+#[derive(EpeeObject, Debug)]
+struct T {
+    val: u8,
+}
+
+/// Encodes an object with one `u8` field with the name `name`.
+fn object_with_name(name: &[u8]) -> Vec<u8> {
+    // Keep the header and field count, removing the encoded `val` field.
+    let mut bytes = to_bytes(&T { val: 1 }).unwrap();
+    bytes.truncate(bytes.len() - 6);
+    bytes.push(name.len() as u8);
+    bytes.extend_from_slice(name);
+    // The `u8` marker and value.
+    bytes.extend_from_slice(&[8, 1]);
+    bytes
+}
+
+#[test]
+fn valid_field_name() {
+    let val: T = from_bytes(&object_with_name(b"val")).unwrap();
+    assert_eq!(val.val, 1);
+}
+
+#[test]
+fn empty_field_name() {
+    let err = from_bytes::<T>(&object_with_name(b"")).unwrap_err();
+    assert!(matches!(err.root(), Error::Format("Field name is empty")));
+}
+
+#[test]
+fn invalid_utf8_field_name() {
+    let err = from_bytes::<T>(&object_with_name(&[0xff, 0xfe])).unwrap_err();
+    assert!(matches!(
+        err.root(),
+        Error::Format("Field name is not valid UTF-8")
+    ));
+}
+
+#[test]
+fn non_utf8_field_name_bytes() {
+    let name = [0xff, 0xfe];
+
+    let mut bytes = Vec::new();
+    write_varint(1, &mut bytes).unwrap();
+    write_field_bytes(&1_u8, &name, &mut bytes).unwrap();
+
+    // The same bytes as an object with the field written by hand, without the header.
+    assert_eq!(bytes, object_with_name(&name)[9..]);
+
+    let mut r = bytes.as_slice();
+    assert_eq!(read_varint(&mut r).unwrap(), 1);
+    assert_eq!(read_field_name_bytes(&mut r).unwrap(), name);
+    assert_eq!(read_epee_value::<u8, _>(&mut r).unwrap(), 1);
+    assert!(r.is_empty());
+}
+
+#[test]
+fn empty_field_name_not_written() {
+    assert!(write_field_bytes(&1_u8, b"", &mut Vec::new()).is_err());
+}
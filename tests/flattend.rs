@@ -1,4 +1,4 @@
-use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+use epee_encoding::{from_bytes, from_bytes_with_remaining, to_bytes, EpeeObject, EpeeObjectWrite};
 
 #[derive(EpeeObject)]
 struct Child {
@@ -67,3 +67,142 @@ fn epee_double_flatten() {
 
     assert_eq!(val, val1);
 }
+
+#[derive(EpeeObject, Default, Debug, PartialEq)]
+struct DefaultedChild {
+    #[epee_default(0)]
+    val: u64,
+    seq: Vec<u32>,
+    opt: Option<u8>,
+}
+
+#[derive(EpeeObject, Default, Debug, PartialEq)]
+struct DefaultedParent {
+    #[epee_flatten]
+    child: DefaultedChild,
+    #[epee_default(1)]
+    h: u8,
+}
+
+#[derive(EpeeObject, Default, Debug, PartialEq)]
+struct DefaultedGrandParent {
+    #[epee_flatten]
+    parent: DefaultedParent,
+    g: u8,
+}
+
+#[derive(EpeeObject)]
+struct Empty {}
+
+#[test]
+fn flattened_child_with_no_written_fields() {
+    let val = DefaultedParent {
+        child: DefaultedChild::default(),
+        h: 1,
+    };
+    assert_eq!(val.number_of_fields(), 0);
+
+    let bytes = to_bytes(&val).unwrap();
+    // The header followed by a field count of 0.
+    assert_eq!(bytes.len(), 10);
+    assert_eq!(bytes[9], 0);
+
+    assert_eq!(from_bytes::<DefaultedParent>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn nested_flattened_child_field_count_matches_written() {
+    let vals = [
+        DefaultedGrandParent::default(),
+        DefaultedGrandParent {
+            parent: DefaultedParent {
+                child: DefaultedChild {
+                    val: 3,
+                    seq: vec![],
+                    opt: Some(2),
+                },
+                h: 1,
+            },
+            g: 4,
+        },
+        DefaultedGrandParent {
+            parent: DefaultedParent {
+                child: DefaultedChild {
+                    val: 0,
+                    seq: vec![5, 6],
+                    opt: None,
+                },
+                h: 7,
+            },
+            g: 0,
+        },
+    ];
+
+    for val in vals {
+        let bytes = to_bytes(&val).unwrap();
+
+        // Skipping every field only works if the field count matches what was written.
+        let (_, remaining) = from_bytes_with_remaining::<Empty>(&bytes).unwrap();
+        assert!(remaining.is_empty());
+
+        assert_eq!(from_bytes::<DefaultedGrandParent>(&bytes).unwrap(), val);
+    }
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct OptionalParent {
+    #[epee_flatten]
+    child: Option<Child1>,
+    h: u8,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct OnlyH {
+    h: u8,
+}
+
+#[test]
+fn flattened_option_absent() {
+    let bytes = to_bytes(&OnlyH { h: 3 }).unwrap();
+
+    let val: OptionalParent = from_bytes(&bytes).unwrap();
+    assert_eq!(val, OptionalParent { child: None, h: 3 });
+
+    assert_eq!(to_bytes(&val).unwrap(), bytes);
+}
+
+#[test]
+fn flattened_option_present() {
+    let val = OptionalParent {
+        child: Some(Child1 {
+            val: 5,
+            val2: vec![1, 2],
+        }),
+        h: 3,
+    };
+    assert_eq!(val.number_of_fields(), 3);
+
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<OptionalParent>(&bytes).unwrap(), val);
+}
+
+#[derive(EpeeObject)]
+struct Val2H {
+    val2: Vec<u8>,
+    h: u8,
+}
+
+#[test]
+fn flattened_option_partial_child_errors() {
+    let bytes = to_bytes(&Val2H {
+        val2: vec![1],
+        h: 3,
+    })
+    .unwrap();
+
+    let err = from_bytes::<OptionalParent>(&bytes).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Format error: Required field `val` was not found!"
+    );
+}
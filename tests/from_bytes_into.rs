@@ -0,0 +1,95 @@
+use epee_encoding::{from_bytes, from_bytes_into, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Blocks {
+    height: u64,
+    blob: Vec<u8>,
+    name: String,
+    txs: Vec<Vec<u8>>,
+    #[epee_default(vec![7])]
+    extra: Vec<u8>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Partial {
+    height: u64,
+    blob: Vec<u8>,
+}
+
+fn blocks() -> Blocks {
+    Blocks {
+        height: 10,
+        blob: vec![1; 100],
+        name: "block".to_string(),
+        txs: vec![vec![2; 50], vec![3; 50]],
+        extra: vec![8, 9],
+    }
+}
+
+#[test]
+fn decodes_into_target() {
+    let val = blocks();
+    let bytes = to_bytes(&val).unwrap();
+
+    let mut target = Blocks {
+        height: 1,
+        blob: vec![],
+        name: String::new(),
+        txs: vec![],
+        extra: vec![],
+    };
+    from_bytes_into(&bytes, &mut target).unwrap();
+    assert_eq!(target, val);
+}
+
+#[test]
+fn allocations_are_reused() {
+    let mut target = blocks();
+    let blob_ptr = target.blob.as_ptr();
+    let txs_ptr = target.txs.as_ptr();
+    let tx_ptr = target.txs[0].as_ptr();
+
+    let val = Blocks {
+        height: 11,
+        blob: vec![4; 80],
+        name: "next".to_string(),
+        txs: vec![vec![5; 40]],
+        extra: vec![6],
+    };
+    from_bytes_into(&to_bytes(&val).unwrap(), &mut target).unwrap();
+
+    assert_eq!(target, val);
+    assert_eq!(target.blob.as_ptr(), blob_ptr);
+    assert_eq!(target.txs.as_ptr(), txs_ptr);
+    assert_eq!(target.txs[0].as_ptr(), tx_ptr);
+}
+
+#[test]
+fn missing_fields_are_not_kept() {
+    let bytes = to_bytes(&Partial {
+        height: 12,
+        blob: vec![1, 2],
+    })
+    .unwrap();
+
+    // `name` is required so decoding errors instead of keeping the old name.
+    let mut target = blocks();
+    assert!(from_bytes_into(&bytes, &mut target).is_err());
+}
+
+#[test]
+fn missing_defaulted_fields_are_reset() {
+    let val = Blocks {
+        height: 12,
+        blob: vec![1, 2],
+        name: "name".to_string(),
+        txs: vec![],
+        extra: vec![7],
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    let mut target = blocks();
+    from_bytes_into(&bytes, &mut target).unwrap();
+    assert_eq!(target, val);
+    assert_eq!(from_bytes::<Blocks>(&bytes).unwrap(), val);
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "std")]
+
+use std::io::{Cursor, Read};
+
+use epee_encoding::{from_reader, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Message {
+    height: u64,
+    blob: Vec<u8>,
+    names: Vec<String>,
+}
+
+fn message() -> Message {
+    Message {
+        height: 100,
+        blob: vec![1; 50],
+        names: vec!["a".to_string(), "bc".to_string()],
+    }
+}
+
+/// A reader which returns at most one byte on each read.
+struct OneByteReader<R>(R);
+
+impl<R: Read> Read for OneByteReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(1);
+        self.0.read(&mut buf[..len])
+    }
+}
+
+#[test]
+fn from_reader_round_trip() {
+    let bytes = to_bytes(&message()).unwrap();
+
+    let val: Message = from_reader(Cursor::new(&bytes)).unwrap();
+    assert_eq!(val, message());
+}
+
+#[test]
+fn from_reader_short_reads() {
+    let bytes = to_bytes(&message()).unwrap();
+
+    let val: Message = from_reader(OneByteReader(Cursor::new(&bytes))).unwrap();
+    assert_eq!(val, message());
+}
+
+#[test]
+fn from_reader_only_reads_object() {
+    let mut bytes = to_bytes(&message()).unwrap();
+    let len = bytes.len();
+    bytes.extend_from_slice(&[0xff; 4]);
+
+    let mut cursor = Cursor::new(&bytes);
+    let val: Message = from_reader(&mut cursor).unwrap();
+    assert_eq!(val, message());
+    assert_eq!(cursor.position(), len as u64);
+}
+
+#[test]
+fn from_reader_back_to_back() {
+    let second = Message {
+        height: 101,
+        blob: vec![2; 10],
+        names: vec![],
+    };
+    let mut bytes = to_bytes(&message()).unwrap();
+    let first_len = bytes.len();
+    bytes.extend_from_slice(&to_bytes(&second).unwrap());
+
+    let mut cursor = Cursor::new(bytes);
+    let val: Message = from_reader(&mut cursor).unwrap();
+    assert_eq!(val, message());
+    assert_eq!(cursor.position(), first_len as u64);
+
+    let val: Message = from_reader(&mut cursor).unwrap();
+    assert_eq!(val, second);
+    assert_eq!(cursor.position(), cursor.get_ref().len() as u64);
+
+    assert!(from_reader::<Message, _>(&mut cursor).is_err());
+}
+
+#[test]
+fn from_reader_truncated() {
+    let bytes = to_bytes(&message()).unwrap();
+
+    let err = from_reader::<Message, _>(Cursor::new(&bytes[..bytes.len() - 1]))
+        .err()
+        .unwrap();
+    assert_eq!(err.root().to_string(), "IO error: Reader ran out of bytes");
+}
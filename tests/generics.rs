@@ -0,0 +1,136 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, EpeeObjectWrite};
+
+/// Not an `EpeeValue`.
+#[derive(Debug, PartialEq)]
+struct Levin;
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Msg<N> {
+    val: u64,
+    _marker: PhantomData<N>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct OnlyVal {
+    val: u64,
+}
+
+#[test]
+fn phantom_data_not_encoded() {
+    let msg = Msg::<Levin> {
+        val: 5,
+        _marker: PhantomData,
+    };
+    assert_eq!(msg.number_of_fields(), 1);
+
+    let bytes = to_bytes(&msg).unwrap();
+    assert_eq!(bytes, to_bytes(&OnlyVal { val: 5 }).unwrap());
+    assert_eq!(from_bytes::<Msg<Levin>>(&bytes).unwrap(), msg);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Generic<T, C> {
+    inner: T,
+    list: Vec<T>,
+    #[epee_skip]
+    cache: Option<C>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Flattened<B> {
+    #[epee_flatten]
+    base: B,
+    extra: u8,
+}
+
+#[test]
+fn generic_fields_round_trip() {
+    let val = Generic::<u32, Levin> {
+        inner: 1,
+        list: vec![2, 3],
+        cache: None,
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<Generic<u32, Levin>>(&bytes).unwrap(), val);
+
+    let val = Generic::<String, Levin> {
+        inner: "a".to_string(),
+        list: vec![],
+        cache: Some(Levin),
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(
+        from_bytes::<Generic<String, Levin>>(&bytes).unwrap().inner,
+        "a"
+    );
+}
+
+#[test]
+fn generic_flattened_round_trip() {
+    let val = Flattened {
+        base: OnlyVal { val: 3 },
+        extra: 4,
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<Flattened<OnlyVal>>(&bytes).unwrap(), val);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+#[epee_tag("type")]
+enum Either<L, R> {
+    Left { val: L },
+    Right { val: R },
+}
+
+#[test]
+fn generic_enum_round_trip() {
+    let val = Either::<u8, String>::Right {
+        val: "b".to_string(),
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<Either<u8, String>>(&bytes).unwrap(), val);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Borrowed<'a> {
+    name: Cow<'a, str>,
+}
+
+#[test]
+fn lifetime_round_trip() {
+    let val = Borrowed {
+        name: Cow::Borrowed("node"),
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<Borrowed>(&bytes).unwrap(), val);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Phantoms<N> {
+    a: PhantomData<N>,
+    b: std::marker::PhantomData<(N, Levin)>,
+}
+
+#[derive(EpeeObject)]
+struct PhantomName {
+    a: u64,
+}
+
+#[test]
+fn phantom_data_is_skipped() {
+    let val = Phantoms::<Levin> {
+        a: PhantomData,
+        b: PhantomData,
+    };
+    assert_eq!(val.number_of_fields(), 0);
+
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(bytes.len(), 10);
+
+    // A field with the same name as a `PhantomData` is skipped.
+    let bytes = to_bytes(&PhantomName { a: 1 }).unwrap();
+    assert_eq!(from_bytes::<Phantoms<Levin>>(&bytes).unwrap(), val);
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use epee_encoding::{
+    from_bytes_with_handlers, read_epee_bytes_borrowed, read_epee_value, to_bytes, EpeeObject,
+    Error, FieldHandler, Value,
+};
+
+#[derive(EpeeObject)]
+struct Full {
+    val: u8,
+    extra: u64,
+    other: String,
+}
+
+#[derive(EpeeObject)]
+struct Known {
+    val: u8,
+}
+
+#[test]
+fn handler_invoked_for_unknown_field() {
+    let bytes = to_bytes(&Full {
+        val: 3,
+        extra: 900,
+        other: "skipped".to_string(),
+    })
+    .unwrap();
+
+    let mut extra = None;
+    let mut handlers: BTreeMap<&str, FieldHandler<'_, '_>> = BTreeMap::new();
+    handlers.insert(
+        "extra",
+        Box::new(|r| {
+            extra = Some(read_epee_value::<u64, _>(r)?);
+            Ok(())
+        }),
+    );
+
+    let val: Known = from_bytes_with_handlers(&bytes, &mut handlers).unwrap();
+    drop(handlers);
+
+    assert_eq!(val.val, 3);
+    assert_eq!(extra, Some(900));
+}
+
+#[test]
+fn handler_error_is_returned() {
+    let bytes = to_bytes(&Full {
+        val: 3,
+        extra: 900,
+        other: "skipped".to_string(),
+    })
+    .unwrap();
+
+    let mut handlers: BTreeMap<&str, FieldHandler<'_, '_>> = BTreeMap::new();
+    handlers.insert(
+        "other",
+        Box::new(|r| read_epee_value::<u64, _>(r).map(|_| ())),
+    );
+
+    assert!(from_bytes_with_handlers::<Known>(&bytes, &mut handlers).is_err());
+}
+
+#[derive(EpeeObject)]
+struct Block {
+    height: u64,
+    blob: Vec<u8>,
+}
+
+#[derive(EpeeObject)]
+struct Height {
+    height: u64,
+}
+
+#[test]
+fn handler_borrows_bytes() {
+    let bytes = to_bytes(&Block {
+        height: 10,
+        blob: vec![8; 1000],
+    })
+    .unwrap();
+
+    let mut blob = None;
+    let mut handlers: BTreeMap<&str, FieldHandler<'_, '_>> = BTreeMap::new();
+    handlers.insert(
+        "blob",
+        Box::new(|r| {
+            blob = Some(read_epee_bytes_borrowed(r)?);
+            Ok(())
+        }),
+    );
+
+    let val: Height = from_bytes_with_handlers(&bytes, &mut handlers).unwrap();
+    drop(handlers);
+
+    let blob = blob.unwrap();
+    assert_eq!(val.height, 10);
+    assert_eq!(blob, [8; 1000]);
+    // The blob is borrowed from `bytes` not copied.
+    assert!(bytes.as_ptr_range().contains(&blob.as_ptr()));
+}
+
+#[derive(EpeeObject)]
+struct Nested {
+    nested: Option<Box<Nested>>,
+}
+
+#[derive(EpeeObject)]
+struct Outer {
+    val: u8,
+    nested: Nested,
+}
+
+#[test]
+fn handler_reads_are_depth_limited() {
+    let mut nested = Nested { nested: None };
+    for _ in 0..200 {
+        nested = Nested {
+            nested: Some(Box::new(nested)),
+        };
+    }
+    let bytes = to_bytes(&Outer { val: 1, nested }).unwrap();
+
+    let mut handlers: BTreeMap<&str, FieldHandler<'_, '_>> = BTreeMap::new();
+    handlers.insert(
+        "nested",
+        Box::new(|r| read_epee_value::<Value, _>(r).map(|_| ())),
+    );
+
+    match from_bytes_with_handlers::<Known>(&bytes, &mut handlers) {
+        Err(err) => assert!(matches!(err.root(), Error::Format(_))),
+        Ok(_) => panic!("expected the depth limit to be exceeded"),
+    }
+}
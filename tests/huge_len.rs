@@ -0,0 +1,50 @@
+use epee_encoding::{from_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug)]
+struct Seq {
+    seq: Vec<u64>,
+}
+
+#[derive(EpeeObject, Debug)]
+struct ObjSeq {
+    seq: Vec<Seq>,
+}
+
+#[derive(EpeeObject, Debug)]
+struct Blob {
+    seq: Vec<u8>,
+}
+
+/// The header and a field named `seq` with `marker` followed by the largest varint.
+fn huge_len(marker: u8) -> Vec<u8> {
+    let mut data = vec![
+        0x01, 0x11, 0x01, 0x1, 0x01, 0x01, 0x02, 0x1, 0x1, 0x04, 0x03, b's', b'e', b'q', marker,
+    ];
+    data.extend_from_slice(&u64::MAX.to_le_bytes());
+    data
+}
+
+#[test]
+fn huge_seq_len_does_not_allocate() {
+    assert!(from_bytes::<Seq>(&huge_len(0x85)).is_err());
+    assert!(from_bytes::<ObjSeq>(&huge_len(0x8C)).is_err());
+}
+
+#[test]
+fn huge_byte_array_len_does_not_allocate() {
+    let mut data = huge_len(0x0A);
+    // The maximum allowed length of a byte array.
+    data.truncate(15);
+    data.extend_from_slice(&((2_000_000_000_u64 << 2) | 0b11).to_le_bytes());
+
+    assert!(from_bytes::<Blob>(&data).is_err());
+}
+
+#[test]
+fn field_count_above_bound() {
+    // The header and an object with the largest varint as the number of fields.
+    let mut data = vec![0x01, 0x11, 0x01, 0x1, 0x01, 0x01, 0x02, 0x1, 0x1];
+    data.extend_from_slice(&u64::MAX.to_le_bytes());
+
+    assert!(from_bytes::<Blob>(&data).is_err());
+}
@@ -0,0 +1,86 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Difficulty {
+    cumulative_difficulty: u128,
+    delta: i128,
+}
+
+#[derive(EpeeObject)]
+struct ShortDifficulty {
+    cumulative_difficulty: Vec<u8>,
+    delta: i128,
+}
+
+#[test]
+fn int128_round_trip() {
+    let val = Difficulty {
+        cumulative_difficulty: u128::MAX,
+        delta: i128::MIN,
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(val, from_bytes(&bytes).unwrap());
+}
+
+#[test]
+fn int128_encoded_as_bytes() {
+    let val = Difficulty {
+        cumulative_difficulty: 1,
+        delta: -1,
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    let mut expected = vec![0x0a, 0x40, 1];
+    expected.extend_from_slice(&[0; 15]);
+    assert!(bytes.windows(expected.len()).any(|w| w == expected));
+}
+
+#[test]
+fn int128_wrong_length() {
+    let bytes = to_bytes(&ShortDifficulty {
+        cumulative_difficulty: vec![1; 15],
+        delta: 0,
+    })
+    .unwrap();
+
+    assert!(from_bytes::<Difficulty>(&bytes).is_err());
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Difficulties {
+    cumulative_difficulties: Vec<u128>,
+    deltas: Vec<i128>,
+}
+
+#[derive(EpeeObject)]
+struct DifficultyBytes {
+    cumulative_difficulties: Vec<Vec<u8>>,
+}
+
+#[test]
+fn int128_seq_round_trip() {
+    let val = Difficulties {
+        cumulative_difficulties: vec![0, 1, u128::MAX],
+        deltas: vec![i128::MIN, -1],
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(val, from_bytes(&bytes).unwrap());
+
+    // Each element is a 16 byte string.
+    let as_bytes: DifficultyBytes = from_bytes(&bytes).unwrap();
+    assert_eq!(as_bytes.cumulative_difficulties[1], 1_u128.to_le_bytes());
+}
+
+#[test]
+fn empty_int128_seq_not_written() {
+    let val = Difficulties {
+        cumulative_difficulties: vec![],
+        deltas: vec![],
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(bytes.len(), 10);
+    assert_eq!(val, from_bytes(&bytes).unwrap());
+}
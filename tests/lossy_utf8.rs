@@ -0,0 +1,47 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject)]
+struct Raw {
+    name: Vec<u8>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Strict {
+    name: String,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Lossy {
+    #[epee_lossy_utf8]
+    name: String,
+}
+
+#[test]
+fn invalid_utf8() {
+    let bytes = to_bytes(&Raw {
+        name: vec![b'a', 0xff, b'b'],
+    })
+    .unwrap();
+
+    assert!(from_bytes::<Strict>(&bytes).is_err());
+    assert_eq!(from_bytes::<Lossy>(&bytes).unwrap().name, "a\u{FFFD}b");
+    // The raw bytes can still be read with a `Vec<u8>`.
+    assert_eq!(from_bytes::<Raw>(&bytes).unwrap().name, [b'a', 0xff, b'b']);
+}
+
+#[test]
+fn valid_utf8() {
+    let val = Lossy {
+        name: "hello".to_string(),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(
+        bytes,
+        to_bytes(&Strict {
+            name: "hello".to_string()
+        })
+        .unwrap()
+    );
+    assert_eq!(from_bytes::<Lossy>(&bytes).unwrap(), val);
+}
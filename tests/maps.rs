@@ -0,0 +1,167 @@
+#![cfg(feature = "std")]
+
+use std::collections::{BTreeMap, HashMap};
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject)]
+struct Votes {
+    v15: u64,
+    v16: u64,
+}
+
+#[derive(EpeeObject)]
+struct GetInfo {
+    height: u64,
+    status: String,
+    hard_fork_votes: Votes,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct GetInfoDynamic {
+    height: u64,
+    status: String,
+    hard_fork_votes: HashMap<String, u64>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Nested {
+    val: u8,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Maps {
+    numbs: HashMap<String, u64>,
+    seqs: HashMap<String, Vec<String>>,
+    objects: HashMap<String, Nested>,
+}
+
+#[test]
+fn get_info_dynamic_keys() {
+    let bytes = to_bytes(&GetInfo {
+        height: 3000000,
+        status: "OK".to_string(),
+        hard_fork_votes: Votes { v15: 10, v16: 200 },
+    })
+    .unwrap();
+
+    let val: GetInfoDynamic = from_bytes(&bytes).unwrap();
+
+    assert_eq!(val.height, 3000000);
+    assert_eq!(val.status, "OK");
+    assert_eq!(val.hard_fork_votes.len(), 2);
+    assert_eq!(val.hard_fork_votes["v15"], 10);
+    assert_eq!(val.hard_fork_votes["v16"], 200);
+}
+
+#[test]
+fn empty_maps_round_trip() {
+    let val = Maps {
+        numbs: HashMap::new(),
+        seqs: HashMap::new(),
+        objects: HashMap::new(),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(bytes.len(), 10);
+    assert_eq!(val, from_bytes(&bytes).unwrap());
+}
+
+#[test]
+fn maps_round_trip() {
+    let val = Maps {
+        numbs: HashMap::from([("a".to_string(), 1), ("b".to_string(), u64::MAX)]),
+        seqs: HashMap::from([
+            ("c".to_string(), vec!["x".to_string(), "yz".to_string()]),
+            ("empty".to_string(), vec![]),
+        ]),
+        objects: HashMap::from([("d".to_string(), Nested { val: 5 })]),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    let decoded: Maps = from_bytes(&bytes).unwrap();
+
+    // empty sequences are not written.
+    assert!(!decoded.seqs.contains_key("empty"));
+    assert_eq!(decoded.numbs, val.numbs);
+    assert_eq!(decoded.seqs["c"], val.seqs["c"]);
+    assert_eq!(decoded.objects, val.objects);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct SortedMap {
+    map: BTreeMap<String, Vec<u8>>,
+}
+
+#[test]
+fn btree_map_deterministic() {
+    let val = SortedMap {
+        map: BTreeMap::from([
+            ("zz".to_string(), vec![1]),
+            ("a".to_string(), vec![2, 3]),
+            ("m".to_string(), vec![]),
+        ]),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    let decoded: SortedMap = from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.map.len(), 3);
+
+    let bytes2 = to_bytes(&decoded).unwrap();
+    assert_eq!(bytes, bytes2);
+
+    let a = bytes.windows(2).position(|w| w == [1, b'a']).unwrap();
+    let zz = bytes.windows(3).position(|w| w == [2, b'z', b'z']).unwrap();
+    assert!(a < zz);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct IntKeys {
+    by_height: BTreeMap<u64, String>,
+    counts: HashMap<u64, u32>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct StringKeys {
+    by_height: BTreeMap<String, String>,
+    counts: HashMap<String, u32>,
+}
+
+#[test]
+fn int_keys_encoded_as_strings() {
+    let val = IntKeys {
+        by_height: BTreeMap::from([(1, "a".to_string()), (u64::MAX, "b".to_string())]),
+        counts: HashMap::from([(0, 5)]),
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<IntKeys>(&bytes).unwrap(), val);
+
+    let strings: StringKeys = from_bytes(&bytes).unwrap();
+    assert_eq!(
+        strings.by_height.keys().collect::<Vec<_>>(),
+        ["1", "18446744073709551615"]
+    );
+    assert_eq!(strings.counts["0"], 5);
+}
+
+#[test]
+fn non_numeric_keys_rejected() {
+    for key in [
+        "a",
+        "+1",
+        "-1",
+        "1.0",
+        " 1",
+        "18446744073709551616",
+        "007",
+        "00",
+    ] {
+        let bytes = to_bytes(&StringKeys {
+            by_height: BTreeMap::from([(key.to_string(), "x".to_string())]),
+            counts: HashMap::new(),
+        })
+        .unwrap();
+
+        assert!(from_bytes::<IntKeys>(&bytes).is_err(), "{}", key);
+    }
+}
@@ -0,0 +1,117 @@
+use epee_encoding::{
+    read_epee_value_and_marker, read_epee_value_expecting, read_marker, Error, InnerMarker, Marker,
+    Value,
+};
+
+#[test]
+fn marker_accessors() {
+    let marker = Marker::new(InnerMarker::U32);
+    assert_eq!(marker.inner_marker(), &InnerMarker::U32);
+    assert!(!marker.is_seq());
+
+    let seq = marker.into_seq();
+    assert_eq!(seq.inner_marker(), &InnerMarker::U32);
+    assert!(seq.is_seq());
+    assert_eq!(seq.as_u8(), 0x86);
+}
+
+#[test]
+fn marker_from_u8_round_trip() {
+    for val in (1..=12).chain(0x81..=0x8C) {
+        let marker = Marker::try_from(val).unwrap();
+        assert_eq!(marker.as_u8(), val);
+    }
+
+    assert!(Marker::try_from(0).is_err());
+}
+
+#[test]
+fn read_marker_custom_match() {
+    let mut r: &[u8] = &[0x8A];
+    let marker = read_marker(&mut r).unwrap();
+
+    assert!(matches!(marker.inner_marker(), InnerMarker::String));
+    assert!(marker.is_seq());
+}
+
+#[test]
+fn read_value_and_marker() {
+    let mut r: &[u8] = &[0x86, 0x08, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00];
+    let (val, marker): (Vec<u32>, _) = read_epee_value_and_marker(&mut r).unwrap();
+    assert_eq!(val, [1, 2]);
+    assert_eq!(marker, Marker::new(InnerMarker::U32).into_seq());
+    assert!(r.is_empty());
+
+    let mut r: &[u8] = &[0x05, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    let (val, marker): (Value, _) = read_epee_value_and_marker(&mut r).unwrap();
+    assert_eq!(val, Value::U64(1));
+    assert_eq!(marker, Marker::new(InnerMarker::U64));
+}
+
+#[test]
+fn read_epee_value_expecting_names_markers() {
+    let mut r: &[u8] = &[0x06, 0x01, 0x00, 0x00, 0x00];
+    let val: u32 = read_epee_value_expecting(&mut r, InnerMarker::U32).unwrap();
+    assert_eq!(val, 1);
+
+    let mut r: &[u8] = &[0x0A, 0x00];
+    let err = read_epee_value_expecting::<u32, _>(&mut r, InnerMarker::U32)
+        .err()
+        .unwrap();
+    assert!(matches!(
+        err,
+        Error::UnexpectedMarker {
+            expected: Marker {
+                inner_marker: InnerMarker::U32,
+                is_seq: false
+            },
+            found: Marker {
+                inner_marker: InnerMarker::String,
+                is_seq: false
+            }
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Format error: Expected marker `u32` but found `string`"
+    );
+}
+
+#[test]
+fn write_field_with_runtime_marker() {
+    let mut expected = Vec::new();
+    epee_encoding::write_field(&vec![1_u32, 2], "val", &mut expected).unwrap();
+
+    let mut buf = Vec::new();
+    let marker = Marker::new(InnerMarker::U32).into_seq();
+    epee_encoding::write_field_with_marker("val", &marker, &mut buf, |w| {
+        // The sequence length as a varint followed by the elements.
+        w.extend_from_slice(&[2 << 2]);
+        w.extend_from_slice(&1_u32.to_le_bytes());
+        w.extend_from_slice(&2_u32.to_le_bytes());
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn seq_of_seq_is_an_error() {
+    let seq = Marker::new(InnerMarker::U64).try_into_seq().unwrap();
+    assert_eq!(seq, Marker::new(InnerMarker::U64).into_seq());
+
+    let err = seq.try_into_seq().unwrap_err();
+    assert!(matches!(err, Error::Format(_)));
+}
+
+#[test]
+fn marker_display() {
+    assert_eq!(Marker::new(InnerMarker::U64).to_string(), "u64");
+    assert_eq!(Marker::new(InnerMarker::String).to_string(), "string");
+    assert_eq!(
+        Marker::new(InnerMarker::Object).into_seq().to_string(),
+        "seq<object>"
+    );
+    assert_eq!(InnerMarker::Bool.to_string(), "bool");
+}
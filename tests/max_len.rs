@@ -0,0 +1,20 @@
+use epee_encoding::{to_bytes, EpeeObject, Error};
+
+#[derive(EpeeObject)]
+struct Blob {
+    blob: Vec<u8>,
+}
+
+#[test]
+fn encoding_over_long_blob_errors() {
+    // Zeroed allocations are lazily mapped so this doesn't use 2GB of memory.
+    let blob = Blob {
+        blob: vec![0; 2_000_000_001],
+    };
+
+    let err = to_bytes(&blob).err().unwrap();
+    assert!(matches!(
+        err.root(),
+        Error::Value("Byte array exceeded max length")
+    ));
+}
@@ -0,0 +1,239 @@
+#![cfg(feature = "std")]
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, EpeeObjectWrite};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct PeerList {
+    local_peerlist: Vec<SocketAddrV4>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct IpList {
+    ips: Vec<Ipv4Addr>,
+}
+
+#[test]
+fn peer_list() {
+    let bytes = [
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x04, 0x0e, b'l', b'o', b'c', b'a',
+        b'l', b'_', b'p', b'e', b'e', b'r', b'l', b'i', b's', b't', 0x8c, 0x08, // 2 objects
+        0x08, 0x04, b'm', b'_', b'i', b'p', 0x06, 192, 168, 0, 1, 0x06, b'm', b'_', b'p', b'o',
+        b'r', b't', 0x07, 0xa0, 0x46, // 192.168.0.1:18080
+        0x08, 0x04, b'm', b'_', b'i', b'p', 0x06, 10, 0, 0, 2, 0x06, b'm', b'_', b'p', b'o', b'r',
+        b't', 0x07, 0xa1, 0x46, // 10.0.0.2:18081
+    ];
+
+    let val: PeerList = from_bytes(&bytes).unwrap();
+    assert_eq!(
+        val.local_peerlist,
+        vec![
+            SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 1), 18080),
+            SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 2), 18081),
+        ]
+    );
+
+    assert_eq!(to_bytes(&val).unwrap(), bytes);
+}
+
+#[test]
+fn ip_list() {
+    let bytes = [
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x04, 0x03, b'i', b'p', b's', 0x86,
+        0x08, 127, 0, 0, 1, 1, 2, 3, 4,
+    ];
+
+    let val: IpList = from_bytes(&bytes).unwrap();
+    assert_eq!(
+        val.ips,
+        vec![Ipv4Addr::new(127, 0, 0, 1), Ipv4Addr::new(1, 2, 3, 4)]
+    );
+
+    assert_eq!(to_bytes(&val).unwrap(), bytes);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Ipv6 {
+    ip: Ipv6Addr,
+}
+
+#[test]
+fn ipv6_as_bytes() {
+    let ip = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+    let bytes = to_bytes(&Ipv6 { ip }).unwrap();
+
+    let mut expected = vec![
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x04, 0x02, b'i', b'p', 0x0a, 0x40,
+    ];
+    expected.extend_from_slice(&ip.octets());
+    assert_eq!(bytes, expected);
+
+    assert_eq!(from_bytes::<Ipv6>(&bytes).unwrap().ip, ip);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Addrs {
+    addrs: Vec<IpAddr>,
+}
+
+#[test]
+fn ip_addr_round_trip() {
+    let val = Addrs {
+        addrs: vec![
+            IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)),
+            IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ],
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(from_bytes::<Addrs>(&bytes).unwrap(), val);
+}
+
+#[derive(EpeeObject)]
+struct WrongType {
+    r#type: u8,
+    addr: Ipv4Addr,
+}
+
+#[derive(EpeeObject)]
+struct WrongTypeAddr {
+    addr: WrongType,
+}
+
+#[derive(EpeeObject, Debug)]
+struct Addr {
+    addr: IpAddr,
+}
+
+#[test]
+fn ip_addr_type_mismatch() {
+    let bytes = to_bytes(&WrongTypeAddr {
+        addr: WrongType {
+            r#type: 2,
+            addr: Ipv4Addr::LOCALHOST,
+        },
+    })
+    .unwrap();
+
+    assert!(from_bytes::<Addr>(&bytes).is_err());
+}
+
+#[test]
+fn socket_addr_size_hint() {
+    let addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 18080);
+    let bytes = to_bytes(&addr).unwrap();
+
+    assert_eq!(addr.size_hint(), bytes.len() - 9 - 1);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct SocketAddrs {
+    addrs: Vec<SocketAddr>,
+}
+
+#[test]
+fn socket_addr_round_trip() {
+    let val = SocketAddrs {
+        addrs: vec![
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 1), 18080)),
+            SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 18081, 0, 0)),
+        ],
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(from_bytes::<SocketAddrs>(&bytes).unwrap(), val);
+}
+
+#[derive(EpeeObject)]
+struct V4NetworkAddress {
+    r#type: u8,
+    addr: V4Addr,
+}
+
+#[derive(EpeeObject)]
+struct V4Addr {
+    m_ip: u32,
+    m_port: u16,
+}
+
+#[derive(EpeeObject)]
+struct V6NetworkAddress {
+    r#type: u8,
+    addr: V6Addr,
+}
+
+#[derive(EpeeObject)]
+struct V6Addr {
+    addr: [u8; 16],
+    m_port: u16,
+}
+
+#[derive(EpeeObject, Debug)]
+struct SingleSocketAddr {
+    addr: SocketAddr,
+}
+
+#[derive(EpeeObject)]
+struct NetworkAddr<T: EpeeObject> {
+    addr: T,
+}
+
+#[test]
+fn socket_addr_monerod_layout() {
+    let v4 = SocketAddrV4::new(Ipv4Addr::new(1, 2, 3, 4), 18080);
+    let bytes = to_bytes(&NetworkAddr {
+        addr: V4NetworkAddress {
+            r#type: 1,
+            addr: V4Addr {
+                m_ip: u32::from_le_bytes(v4.ip().octets()),
+                m_port: 18080,
+            },
+        },
+    })
+    .unwrap();
+    assert_eq!(
+        bytes,
+        to_bytes(&SingleSocketAddr { addr: v4.into() }).unwrap()
+    );
+    assert_eq!(
+        from_bytes::<SingleSocketAddr>(&bytes).unwrap().addr,
+        SocketAddr::V4(v4)
+    );
+
+    let v6 = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 18080, 0, 0);
+    let bytes = to_bytes(&NetworkAddr {
+        addr: V6NetworkAddress {
+            r#type: 2,
+            addr: V6Addr {
+                addr: v6.ip().octets(),
+                m_port: 18080,
+            },
+        },
+    })
+    .unwrap();
+    assert_eq!(
+        bytes,
+        to_bytes(&SingleSocketAddr { addr: v6.into() }).unwrap()
+    );
+    assert_eq!(
+        from_bytes::<SingleSocketAddr>(&bytes).unwrap().addr,
+        SocketAddr::V6(v6)
+    );
+}
+
+#[test]
+fn socket_addr_wrong_type() {
+    let bytes = to_bytes(&NetworkAddr {
+        addr: V4NetworkAddress {
+            r#type: 2,
+            addr: V4Addr {
+                m_ip: 1,
+                m_port: 18080,
+            },
+        },
+    })
+    .unwrap();
+
+    assert!(from_bytes::<SingleSocketAddr>(&bytes).is_err());
+}
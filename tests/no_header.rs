@@ -0,0 +1,32 @@
+use epee_encoding::{from_bytes, from_bytes_no_header, to_bytes, to_bytes_no_header, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Inner {
+    val: u64,
+    name: String,
+}
+
+fn inner() -> Inner {
+    Inner {
+        val: 4,
+        name: "a".to_string(),
+    }
+}
+
+#[test]
+fn no_header_is_bytes_without_header() {
+    let bytes = to_bytes(&inner()).unwrap();
+    let no_header = to_bytes_no_header(&inner()).unwrap();
+
+    assert_eq!(&bytes[9..], no_header.as_slice());
+    assert_eq!(from_bytes_no_header::<Inner>(&no_header).unwrap(), inner());
+}
+
+#[test]
+fn header_required_unless_no_header() {
+    let no_header = to_bytes_no_header(&inner()).unwrap();
+    assert!(from_bytes::<Inner>(&no_header).is_err());
+
+    let bytes = to_bytes(&inner()).unwrap();
+    assert!(from_bytes_no_header::<Inner>(&bytes).is_err());
+}
@@ -0,0 +1,50 @@
+use std::num::{NonZeroI16, NonZeroU32, NonZeroU64};
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, Error};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct NonZero {
+    id: NonZeroU64,
+    port: NonZeroU32,
+    offset: NonZeroI16,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Ints {
+    id: u64,
+    port: u32,
+    offset: i16,
+}
+
+#[test]
+fn non_zero_encoded_as_inner_int() {
+    let val = NonZero {
+        id: NonZeroU64::new(1).unwrap(),
+        port: NonZeroU32::new(18080).unwrap(),
+        offset: NonZeroI16::new(-5).unwrap(),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(
+        from_bytes::<Ints>(&bytes).unwrap(),
+        Ints {
+            id: 1,
+            port: 18080,
+            offset: -5
+        }
+    );
+    assert_eq!(from_bytes::<NonZero>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn non_zero_rejects_zero() {
+    let bytes = to_bytes(&Ints {
+        id: 1,
+        port: 0,
+        offset: -5,
+    })
+    .unwrap();
+
+    let err = from_bytes::<NonZero>(&bytes).err().unwrap();
+    assert!(matches!(err.root(), Error::Value("Non zero int was zero")));
+}
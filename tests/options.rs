@@ -1,4 +1,4 @@
-use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+use epee_encoding::{from_bytes, to_bytes, to_bytes_no_header, EpeeObject};
 
 #[derive(EpeeObject)]
 struct T {
@@ -24,3 +24,29 @@ fn optional_val_in_data() {
     assert_eq!(bytes.as_slice(), bytes2.as_slice());
     assert_eq!(t.val.unwrap(), 21);
 }
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Item {
+    val: Option<u64>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Sparse {
+    items: Vec<Item>,
+}
+
+#[test]
+fn sparse_seq_with_wrapped_items() {
+    let val = Sparse {
+        items: vec![
+            Item { val: Some(1) },
+            Item { val: None },
+            Item { val: Some(3) },
+        ],
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<Sparse>(&bytes).unwrap(), val);
+
+    // A `None` item is an object with no fields.
+    assert_eq!(to_bytes_no_header(&Item { val: None }).unwrap(), [0x00]);
+}
@@ -0,0 +1,64 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, PackedBools};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Flags {
+    flags: PackedBools,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Bytes {
+    flags: Vec<u8>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct BoolSeq {
+    flags: Vec<bool>,
+}
+
+fn flags(len: usize) -> Flags {
+    Flags {
+        flags: PackedBools((0..len).map(|i| i % 3 == 0).collect()),
+    }
+}
+
+#[test]
+fn round_trips() {
+    for len in [0, 1, 7, 8, 9, 15, 16, 17, 100] {
+        let val = flags(len);
+        let bytes = to_bytes(&val).unwrap();
+        assert_eq!(from_bytes::<Flags>(&bytes).unwrap(), val);
+    }
+}
+
+#[test]
+fn packed_layout() {
+    let val = Flags {
+        flags: PackedBools(vec![
+            true, false, true, true, false, false, false, false, true,
+        ]),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(
+        from_bytes::<Bytes>(&bytes).unwrap().flags,
+        [7, 0b0000_1101, 0b0000_0001]
+    );
+
+    // A `Vec<bool>` takes a byte per `bool`.
+    let val = flags(800);
+    let packed = from_bytes::<Bytes>(&to_bytes(&val).unwrap()).unwrap();
+    assert_eq!(packed.flags.len(), 101);
+    let unpacked = to_bytes(&BoolSeq {
+        flags: val.flags.into(),
+    })
+    .unwrap();
+    assert!(unpacked.len() > 800);
+}
+
+#[test]
+fn invalid_padding_rejected() {
+    for packed in [vec![8, 0], vec![1], vec![7, 0b0000_0011]] {
+        let bytes = to_bytes(&Bytes { flags: packed }).unwrap();
+        assert!(from_bytes::<Flags>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, RawU8Seq};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Raw {
+    data: RawU8Seq,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Bytes {
+    data: Vec<u8>,
+}
+
+#[test]
+fn u8_seq_payload() {
+    let bytes = [
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x04, 0x04, b'd', b'a', b't', b'a',
+        0x88, 0x0c, 1, 2, 3,
+    ];
+
+    let val: Raw = from_bytes(&bytes).unwrap();
+    assert_eq!(val.data, RawU8Seq(vec![1, 2, 3]));
+    assert_eq!(to_bytes(&val).unwrap(), bytes);
+
+    // A `Vec<u8>` expects the string marker.
+    assert!(from_bytes::<Bytes>(&bytes).is_err());
+}
+
+#[test]
+fn string_marker_rejected() {
+    let bytes = to_bytes(&Bytes {
+        data: vec![1, 2, 3],
+    })
+    .unwrap();
+
+    assert!(from_bytes::<Raw>(&bytes).is_err());
+}
+
+#[test]
+fn empty_seq_not_written() {
+    let val = Raw {
+        data: RawU8Seq::default(),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(bytes.len(), 10);
+    assert_eq!(from_bytes::<Raw>(&bytes).unwrap(), val);
+}
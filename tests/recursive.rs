@@ -0,0 +1,71 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Node {
+    value: u64,
+    next: Option<Box<Node>>,
+}
+
+fn linked_list(len: u64) -> Node {
+    let mut node = Node {
+        value: 0,
+        next: None,
+    };
+    for value in 1..len {
+        node = Node {
+            value,
+            next: Some(Box::new(node)),
+        };
+    }
+    node
+}
+
+#[test]
+fn linked_list_round_trip() {
+    let list = linked_list(3);
+    let bytes = to_bytes(&list).unwrap();
+
+    let val: Node = from_bytes(&bytes).unwrap();
+
+    assert_eq!(val, list);
+    assert_eq!(val.value, 2);
+    assert_eq!(val.next.as_ref().unwrap().value, 1);
+    assert!(val.next.unwrap().next.unwrap().next.is_none());
+}
+
+#[test]
+fn linked_list_too_deep() {
+    let bytes = to_bytes(&linked_list(200)).unwrap();
+
+    assert!(from_bytes::<Node>(&bytes).is_err());
+}
+
+#[allow(clippy::vec_box)]
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Tree {
+    value: u64,
+    children: Vec<Box<Tree>>,
+}
+
+#[test]
+fn boxed_object_seq_round_trip() {
+    let tree = Tree {
+        value: 0,
+        children: vec![
+            Box::new(Tree {
+                value: 1,
+                children: vec![],
+            }),
+            Box::new(Tree {
+                value: 2,
+                children: vec![Box::new(Tree {
+                    value: 3,
+                    children: vec![],
+                })],
+            }),
+        ],
+    };
+
+    let bytes = to_bytes(&tree).unwrap();
+    assert_eq!(from_bytes::<Tree>(&bytes).unwrap(), tree);
+}
@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+
+use epee_encoding::{
+    from_bytes, from_bytes_allow_trailing, from_bytes_epee_value, from_bytes_into,
+    from_bytes_no_header, from_bytes_value, from_bytes_with_handlers, from_bytes_with_options,
+    from_bytes_with_remaining, from_bytes_with_stats, to_bytes, to_bytes_epee_value,
+    to_bytes_no_header, DecodeOptions, EpeeObject, Error,
+};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct T {
+    val: u64,
+}
+
+#[test]
+fn remaining_bytes_returned() {
+    let t = T { val: 54 };
+    let mut bytes = to_bytes(&t).unwrap();
+    bytes.extend_from_slice(&[1, 2, 3]);
+
+    let (val, remaining) = from_bytes_with_remaining::<T>(&bytes).unwrap();
+    assert_eq!(val, t);
+    assert_eq!(remaining, [1, 2, 3]);
+}
+
+#[test]
+fn back_to_back_objects() {
+    let mut bytes = to_bytes(&T { val: 1 }).unwrap();
+    bytes.extend(to_bytes(&T { val: 2 }).unwrap());
+
+    let (val, remaining) = from_bytes_with_remaining::<T>(&bytes).unwrap();
+    assert_eq!(val, T { val: 1 });
+
+    let (val, remaining) = from_bytes_with_remaining::<T>(remaining).unwrap();
+    assert_eq!(val, T { val: 2 });
+    assert!(remaining.is_empty());
+}
+
+#[test]
+fn trailing_byte_errors() {
+    let t = T { val: 54 };
+    let mut bytes = to_bytes(&t).unwrap();
+    assert_eq!(from_bytes::<T>(&bytes).unwrap(), t);
+
+    bytes.push(0);
+    assert!(from_bytes::<T>(&bytes).is_err());
+    assert_eq!(from_bytes_allow_trailing::<T>(&bytes).unwrap(), t);
+}
+
+#[test]
+fn trailing_byte_errors_for_every_entry_point() {
+    let t = T { val: 54 };
+    let mut bytes = to_bytes(&t).unwrap();
+    bytes.push(0);
+    let mut no_header = to_bytes_no_header(&t).unwrap();
+    no_header.push(0);
+    let mut value = to_bytes_epee_value(&5_u64).unwrap();
+    value.push(0);
+
+    let is_trailing_err = |err: Error| matches!(err, Error::Format("Data has trailing bytes"));
+
+    assert!(is_trailing_err(
+        from_bytes_with_options::<T>(&bytes, &DecodeOptions::default()).unwrap_err()
+    ));
+    assert!(is_trailing_err(
+        from_bytes_no_header::<T>(&no_header).unwrap_err()
+    ));
+    assert!(is_trailing_err(
+        from_bytes_with_handlers::<T>(&bytes, &mut BTreeMap::new()).unwrap_err()
+    ));
+    assert!(is_trailing_err(from_bytes_value(&bytes).unwrap_err()));
+    assert!(is_trailing_err(
+        from_bytes_with_stats::<T>(&bytes).unwrap_err()
+    ));
+    assert!(is_trailing_err(
+        from_bytes_into(&bytes, &mut T { val: 0 }).unwrap_err()
+    ));
+    assert!(is_trailing_err(
+        from_bytes_epee_value::<u64>(&value).unwrap_err()
+    ));
+
+    // Without the trailing byte they all succeed.
+    bytes.pop();
+    no_header.pop();
+    value.pop();
+    assert_eq!(
+        from_bytes_with_options::<T>(&bytes, &DecodeOptions::default()).unwrap(),
+        t
+    );
+    assert_eq!(from_bytes_no_header::<T>(&no_header).unwrap(), t);
+    assert_eq!(
+        from_bytes_with_handlers::<T>(&bytes, &mut BTreeMap::new()).unwrap(),
+        t
+    );
+    assert!(from_bytes_value(&bytes).is_ok());
+    assert_eq!(from_bytes_with_stats::<T>(&bytes).unwrap().0, t);
+    assert_eq!(from_bytes_epee_value::<u64>(&value).unwrap(), 5);
+}
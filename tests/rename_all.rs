@@ -0,0 +1,88 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+#[epee_rename_all("camelCase")]
+struct Camel {
+    peer_id: u64,
+    last_seen_height: u32,
+    #[epee_alt_name("port")]
+    rpc_port: u16,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+#[allow(non_snake_case)]
+struct CamelWire {
+    peerId: u64,
+    lastSeenHeight: u32,
+    port: u16,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+#[epee_rename_all("PascalCase")]
+struct Pascal {
+    peer_id: u64,
+    r#type: u8,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+#[allow(non_snake_case)]
+struct PascalWire {
+    PeerId: u64,
+    Type: u8,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+#[epee_rename_all("snake_case")]
+struct Snake {
+    peer_id: u64,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct SnakeWire {
+    peer_id: u64,
+}
+
+#[test]
+fn rename_all_camel_case() {
+    let val = Camel {
+        peer_id: 1,
+        last_seen_height: 2,
+        rpc_port: 3,
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(
+        from_bytes::<CamelWire>(&bytes).unwrap(),
+        CamelWire {
+            peerId: 1,
+            lastSeenHeight: 2,
+            port: 3,
+        }
+    );
+    assert_eq!(from_bytes::<Camel>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn rename_all_pascal_case() {
+    let val = Pascal {
+        peer_id: 1,
+        r#type: 2,
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(
+        from_bytes::<PascalWire>(&bytes).unwrap(),
+        PascalWire { PeerId: 1, Type: 2 }
+    );
+    assert_eq!(from_bytes::<Pascal>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn rename_all_snake_case() {
+    let bytes = to_bytes(&Snake { peer_id: 1 }).unwrap();
+
+    assert_eq!(
+        from_bytes::<SnakeWire>(&bytes).unwrap(),
+        SnakeWire { peer_id: 1 }
+    );
+}
@@ -0,0 +1,42 @@
+use epee_encoding::{from_bytes, from_bytes_epee_value, to_bytes, to_bytes_epee_value, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct T {
+    val: u8,
+}
+
+#[test]
+fn seq_root() {
+    let hashes = vec![[1_u8; 32], [2; 32]];
+    let bytes = to_bytes_epee_value(&hashes).unwrap();
+
+    assert_eq!(&bytes[..9], b"\x01\x11\x01\x01\x01\x01\x02\x01\x01");
+    // A sequence of strings with 2 items.
+    assert_eq!(&bytes[9..11], &[0x8a, 0x08]);
+    assert_eq!(
+        from_bytes_epee_value::<Vec<[u8; 32]>>(&bytes).unwrap(),
+        hashes
+    );
+}
+
+#[test]
+fn object_root_has_marker() {
+    let val = T { val: 1 };
+    let bytes = to_bytes_epee_value(&val).unwrap();
+
+    let mut expected = to_bytes(&val).unwrap();
+    expected.insert(9, 0x0c);
+    assert_eq!(bytes, expected);
+
+    assert_eq!(from_bytes_epee_value::<T>(&bytes).unwrap(), val);
+    assert!(from_bytes::<T>(&bytes).is_err());
+}
+
+#[test]
+fn trailing_bytes_error() {
+    let mut bytes = to_bytes_epee_value(&5_u64).unwrap();
+    assert_eq!(from_bytes_epee_value::<u64>(&bytes).unwrap(), 5);
+
+    bytes.push(0);
+    assert!(from_bytes_epee_value::<u64>(&bytes).is_err());
+}
@@ -1,4 +1,6 @@
-use epee_encoding::{from_bytes, EpeeObject};
+use std::collections::VecDeque;
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, Error};
 
 #[derive(EpeeObject)]
 struct ObjSeq {
@@ -50,3 +52,217 @@ fn seq_with_non_zero_len_must_have_correct_marker() {
     data.extend_from_slice(&1_i64.to_le_bytes());
     (from_bytes::<ValSeq>(&data).unwrap());
 }
+
+#[derive(EpeeObject)]
+struct BlobSeq {
+    blobs: Vec<Vec<u8>>,
+}
+
+#[test]
+fn seq_of_blobs_uses_string_seq_marker() {
+    let data = [
+        0x01, 0x11, 0x01, 0x1, 0x01, 0x01, 0x02, 0x1, 0x1, 0x04, 0x05, b'b', b'l', b'o', b'b',
+        b's', 0x8a, // String seq marker
+        0x0c, // varint length of 3
+        0x08, 0xaa, 0xbb, // blob of length 2
+        0x00, // blob of length 0
+        0x0c, 0x01, 0x02, 0x03, // blob of length 3
+    ];
+
+    let val = from_bytes::<BlobSeq>(&data).unwrap();
+    assert_eq!(
+        val.blobs,
+        vec![vec![0xaa, 0xbb], vec![], vec![0x01, 0x02, 0x03]]
+    );
+
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(bytes[16], 0x80 | 10);
+    assert_eq!(bytes, data);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct StringSeq {
+    names: Vec<String>,
+}
+
+#[test]
+fn seq_of_strings_uses_string_seq_marker() {
+    let data = [
+        0x01, 0x11, 0x01, 0x1, 0x01, 0x01, 0x02, 0x1, 0x1, 0x04, 0x05, b'n', b'a', b'm', b'e',
+        b's', 0x8a, // String seq marker
+        0x0c, // varint length of 3
+        0x04, b'a', // string of length 1
+        0x00, // string of length 0
+        0x0c, b'x', b'y', b'z', // string of length 3
+    ];
+
+    let val = from_bytes::<StringSeq>(&data).unwrap();
+    assert_eq!(val.names, ["a", "", "xyz"]);
+    assert_eq!(to_bytes(&val).unwrap(), data);
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Hashes {
+    hashes: Vec<Vec<u8>>,
+    names: Vec<String>,
+}
+
+#[test]
+fn seqs_of_byte_arrays_round_trip() {
+    let val = Hashes {
+        hashes: vec![vec![1; 32], vec![2; 1], vec![], vec![3; 300]],
+        names: vec!["a".repeat(70), String::new(), "bc".to_string()],
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<Hashes>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn seq_of_blobs_rejects_u8_seq_marker() {
+    let data = [
+        0x01, 0x11, 0x01, 0x1, 0x01, 0x01, 0x02, 0x1, 0x1, 0x04, 0x05, b'b', b'l', b'o', b'b',
+        b's', 0x88, // U8 seq marker
+        0x08, 0xaa, 0xbb,
+    ];
+
+    assert!(from_bytes::<BlobSeq>(&data).is_err());
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Peer {
+    id: u64,
+}
+
+#[derive(EpeeObject)]
+struct DequeSeq {
+    peers: VecDeque<Peer>,
+    vals: VecDeque<u32>,
+}
+
+#[derive(EpeeObject)]
+struct VecSeq {
+    peers: Vec<Peer>,
+    vals: Vec<u32>,
+}
+
+#[test]
+fn vec_deque_matches_vec_encoding() {
+    let deque = DequeSeq {
+        peers: VecDeque::from([Peer { id: 1 }, Peer { id: 2 }]),
+        vals: VecDeque::from([3, 4, 5]),
+    };
+
+    let vec = VecSeq {
+        peers: vec![Peer { id: 1 }, Peer { id: 2 }],
+        vals: vec![3, 4, 5],
+    };
+
+    let bytes = to_bytes(&deque).unwrap();
+    assert_eq!(bytes, to_bytes(&vec).unwrap());
+
+    let decoded = from_bytes::<DequeSeq>(&bytes).unwrap();
+    assert_eq!(
+        decoded.peers.iter().map(|p| p.id).collect::<Vec<_>>(),
+        [1, 2]
+    );
+    assert_eq!(decoded.vals, [3, 4, 5]);
+}
+
+#[test]
+fn empty_vec_deque_is_not_written() {
+    let val = DequeSeq {
+        peers: VecDeque::new(),
+        vals: VecDeque::new(),
+    };
+
+    let bytes = to_bytes(&val).unwrap();
+    let decoded = from_bytes::<DequeSeq>(&bytes).unwrap();
+    assert!(decoded.peers.is_empty());
+    assert!(decoded.vals.is_empty());
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Arrays {
+    vals: [u32; 3],
+    peers: [Peer; 2],
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct EmptyArrays {
+    vals: [u32; 0],
+    peers: [Peer; 0],
+}
+
+#[derive(EpeeObject)]
+struct Vecs {
+    vals: Vec<u32>,
+    peers: Vec<Peer>,
+}
+
+#[test]
+fn array_wrong_length() {
+    let bytes = to_bytes(&Vecs {
+        vals: vec![1, 2],
+        peers: vec![Peer { id: 1 }, Peer { id: 2 }],
+    })
+    .unwrap();
+    let err = from_bytes::<Arrays>(&bytes).unwrap_err();
+    assert!(matches!(
+        err.root(),
+        Error::LengthMismatch {
+            expected: 3,
+            found: 2
+        }
+    ));
+    assert_eq!(
+        err.root().to_string(),
+        "Format error: Expected array of length 3 but found length 2"
+    );
+
+    let bytes = to_bytes(&Vecs {
+        vals: vec![1, 2, 3],
+        peers: vec![Peer { id: 1 }],
+    })
+    .unwrap();
+    let err = from_bytes::<Arrays>(&bytes).unwrap_err();
+    assert!(matches!(
+        err.root(),
+        Error::LengthMismatch {
+            expected: 2,
+            found: 1
+        }
+    ));
+}
+
+#[test]
+fn zero_length_arrays() {
+    let val = EmptyArrays {
+        vals: [],
+        peers: [],
+    };
+    let bytes = to_bytes(&val).unwrap();
+    // Empty arrays are not written, like other sequences.
+    assert_eq!(
+        bytes,
+        to_bytes(&Vecs {
+            vals: vec![],
+            peers: vec![]
+        })
+        .unwrap()
+    );
+    assert_eq!(from_bytes::<EmptyArrays>(&bytes).unwrap(), val);
+
+    // A zero length sequence can have any marker.
+    let mut data = [
+        0x01, 0x11, 0x01, 0x1, 0x01, 0x01, 0x02, 0x1, 0x1, 0x04, 0x04, b'v', b'a', b'l', b's',
+    ]
+    .to_vec();
+    for marker in 1..13 {
+        data.push(0x80 | marker);
+        data.push(0);
+
+        assert_eq!(from_bytes::<EmptyArrays>(&data).unwrap(), val);
+
+        data.drain(15..);
+    }
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "std")]
+
+use std::collections::{BTreeSet, HashSet};
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Sets {
+    ids: BTreeSet<u64>,
+    names: HashSet<String>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Seqs {
+    ids: Vec<u64>,
+    names: Vec<String>,
+}
+
+#[test]
+fn sets_round_trip() {
+    let val = Sets {
+        ids: BTreeSet::from([3, 1, 2]),
+        names: HashSet::from(["a".to_string(), "b".to_string()]),
+    };
+
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<Sets>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn btree_set_encoded_as_sorted_seq() {
+    let val = Sets {
+        ids: BTreeSet::from([3, 1, 2]),
+        names: HashSet::new(),
+    };
+
+    let bytes = to_bytes(&val).unwrap();
+    let decoded: Seqs = from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.ids, [1, 2, 3]);
+    // empty sets are not written.
+    assert!(decoded.names.is_empty());
+}
+
+#[test]
+fn set_duplicates_collapse() {
+    let val = Seqs {
+        ids: vec![1, 1, 2],
+        names: vec!["a".to_string(), "a".to_string()],
+    };
+
+    let bytes = to_bytes(&val).unwrap();
+    let decoded: Sets = from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.ids, BTreeSet::from([1, 2]));
+    assert_eq!(decoded.names, HashSet::from(["a".to_string()]));
+}
@@ -0,0 +1,51 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Fragment {
+    height: u64,
+    blob: Vec<u8>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Shared {
+    fragment: Arc<Fragment>,
+    local: Rc<Fragment>,
+    ids: Arc<Vec<u32>>,
+    name: Option<Rc<String>>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Owned {
+    fragment: Fragment,
+    local: Fragment,
+    ids: Vec<u32>,
+    name: Option<String>,
+}
+
+#[test]
+fn shared_ptr_encoded_as_inner() {
+    let fragment = Arc::new(Fragment {
+        height: 1,
+        blob: vec![1, 2],
+    });
+    let shared = Shared {
+        fragment: fragment.clone(),
+        local: Rc::new(Fragment {
+            height: 2,
+            blob: vec![],
+        }),
+        ids: Arc::new(vec![]),
+        name: Some(Rc::new("node".to_string())),
+    };
+
+    let bytes = to_bytes(&shared).unwrap();
+    let owned: Owned = from_bytes(&bytes).unwrap();
+    assert_eq!(owned.fragment, *fragment);
+    assert!(owned.ids.is_empty());
+
+    assert_eq!(to_bytes(&owned).unwrap(), bytes);
+    assert_eq!(from_bytes::<Shared>(&bytes).unwrap(), shared);
+}
@@ -0,0 +1,130 @@
+use epee_encoding::{to_bytes, EpeeObject, EpeeObjectWrite};
+
+#[derive(EpeeObject)]
+struct Fixed {
+    a: u8,
+    b: i64,
+    c: f32,
+    d: bool,
+}
+
+#[derive(EpeeObject)]
+struct Parent {
+    #[epee_flatten]
+    fixed: Fixed,
+    #[epee_flatten]
+    opt: Option<Fixed>,
+    val: u32,
+}
+
+#[derive(EpeeObject)]
+struct Variable {
+    a: u16,
+    blob: Vec<u8>,
+}
+
+#[derive(EpeeObject)]
+#[epee_tag("type")]
+enum Message {
+    Ping,
+    Pong { height: u64 },
+}
+
+fn fixed() -> Fixed {
+    Fixed {
+        a: 1,
+        b: 2,
+        c: 3.0,
+        d: true,
+    }
+}
+
+fn encoded_len<T: EpeeObject>(val: &T) -> usize {
+    // Take away the header and the number of fields.
+    to_bytes(val).unwrap().len() - 9 - 1
+}
+
+#[test]
+fn size_hint_exact_for_fixed_fields() {
+    let val = fixed();
+    assert_eq!(val.size_hint(), encoded_len(&val));
+
+    let val = Parent {
+        fixed: fixed(),
+        opt: Some(fixed()),
+        val: 5,
+    };
+    assert_eq!(val.size_hint(), encoded_len(&val));
+
+    for val in [Message::Ping, Message::Pong { height: 10 }] {
+        assert_eq!(val.size_hint(), encoded_len(&val));
+    }
+}
+
+#[test]
+fn size_hint_counts_only_fixed_fields() {
+    let val = Variable {
+        a: 1,
+        blob: vec![0; 100],
+    };
+    let fixed_len = 1 + 1 + 1 + 2;
+    assert_eq!(val.size_hint(), fixed_len);
+    assert!(val.size_hint() < encoded_len(&val));
+}
+
+#[allow(non_camel_case_types)]
+mod shadowed {
+    use epee_encoding::EpeeObject;
+
+    // Not the primitive, so must not be treated as a fixed size field.
+    pub type i16 = Vec<std::primitive::u64>;
+
+    #[derive(EpeeObject)]
+    pub struct Shadowed {
+        pub a: i16,
+    }
+}
+
+#[test]
+fn shadowed_primitive_is_not_fixed() {
+    let val = shadowed::Shadowed { a: vec![] };
+    assert_eq!(val.size_hint(), 0);
+    assert_eq!(encoded_len(&val), 0);
+
+    let val = shadowed::Shadowed { a: vec![1] };
+    assert_eq!(val.size_hint(), 0);
+    assert_eq!(
+        to_bytes(&val).unwrap()[9..],
+        [4, 1, b'a', 0x85, 4, 1, 0, 0, 0, 0, 0, 0, 0][..]
+    );
+}
+
+#[derive(Clone, Copy)]
+struct Wide(u32);
+
+impl From<Wide> for u8 {
+    fn from(value: Wide) -> Self {
+        value.0 as u8
+    }
+}
+
+impl TryFrom<u8> for Wide {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Ok(Wide(value.into()))
+    }
+}
+
+#[derive(EpeeObject)]
+struct Converted {
+    #[epee_try_from_into(u8)]
+    a: Wide,
+}
+
+#[test]
+fn size_hint_uses_converted_type() {
+    let val = Converted { a: Wide(3) };
+    assert_eq!(val.size_hint(), 1 + 1 + 1 + 1);
+    assert_eq!(val.size_hint(), encoded_len(&val));
+}
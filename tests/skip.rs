@@ -0,0 +1,57 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(Debug, PartialEq)]
+struct Cache(Vec<u64>);
+
+impl Default for Cache {
+    fn default() -> Self {
+        Cache(vec![1, 2, 3])
+    }
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct WithSkip {
+    val: u8,
+    #[epee_skip]
+    cache: Cache,
+    #[epee_skip]
+    computed: u64,
+    other: String,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct WithoutSkip {
+    val: u8,
+    other: String,
+}
+
+#[test]
+fn skipped_fields_not_encoded() {
+    let val = WithSkip {
+        val: 3,
+        cache: Cache(vec![]),
+        computed: 99,
+        other: "hi".to_string(),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(
+        bytes,
+        to_bytes(&WithoutSkip {
+            val: 3,
+            other: "hi".to_string()
+        })
+        .unwrap()
+    );
+
+    let decoded: WithSkip = from_bytes(&bytes).unwrap();
+    assert_eq!(
+        decoded,
+        WithSkip {
+            val: 3,
+            cache: Cache::default(),
+            computed: 0,
+            other: "hi".to_string(),
+        }
+    );
+}
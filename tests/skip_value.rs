@@ -0,0 +1,110 @@
+use epee_encoding::io::{Read, Write};
+use epee_encoding::{
+    from_bytes, read_epee_value, skip_epee_value, to_bytes, write_field, EpeeObject,
+    EpeeObjectBuilder, EpeeObjectWrite, Error, Result,
+};
+
+#[derive(EpeeObject)]
+struct Inner {
+    val: u8,
+}
+
+#[derive(EpeeObject)]
+struct Full {
+    val: u8,
+    seq: Vec<String>,
+    obj: Inner,
+    objs: Vec<Inner>,
+}
+
+/// Only reads `val`, counting the other fields it skips.
+struct Custom {
+    val: u8,
+    skipped: u32,
+}
+
+#[derive(Default)]
+struct CustomBuilder {
+    val: Option<u8>,
+    skipped: u32,
+}
+
+impl EpeeObjectBuilder<Custom> for CustomBuilder {
+    fn add_field<R: Read>(&mut self, name: &str, r: &mut R) -> Result<bool> {
+        match name {
+            "val" => self.val = Some(read_epee_value(r)?),
+            _ => {
+                skip_epee_value(r)?;
+                self.skipped += 1;
+            }
+        }
+        Ok(true)
+    }
+
+    fn finish(self) -> Result<Custom> {
+        Ok(Custom {
+            val: self
+                .val
+                .ok_or(Error::Format("Required field `val` was not found!"))?,
+            skipped: self.skipped,
+        })
+    }
+}
+
+impl EpeeObject for Custom {
+    type Builder = CustomBuilder;
+}
+
+impl EpeeObjectWrite for Custom {
+    fn number_of_fields(&self) -> u64 {
+        1
+    }
+
+    fn write_fields<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_field(&self.val, "val", w)
+    }
+}
+
+#[test]
+fn custom_builder_skips_unknown_fields() {
+    let bytes = to_bytes(&Full {
+        val: 4,
+        seq: vec!["a".to_string(), "b".to_string()],
+        obj: Inner { val: 1 },
+        objs: vec![Inner { val: 2 }, Inner { val: 3 }],
+    })
+    .unwrap();
+
+    let val: Custom = from_bytes(&bytes).unwrap();
+    assert_eq!(val.val, 4);
+    assert_eq!(val.skipped, 3);
+}
+
+#[test]
+fn skip_value_truncated() {
+    // A u64 marker with only 4 bytes.
+    let mut r: &[u8] = &[0x05, 0, 0, 0, 0];
+    assert!(skip_epee_value(&mut r).is_err());
+}
+
+#[test]
+fn skip_seq_of_seq_rejected() {
+    // The header then a field `val` and an unknown field `seqs`, which must be skipped,
+    // with monerod's marker for a sequence of sequences.
+    let bytes = [
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x08, 0x03, b'v', b'a', b'l', 0x08,
+        4, 0x04, b's', b'e', b'q', b's', 0x8D, 0x04, 0x85, 0x04, 1, 0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    let err = from_bytes::<Inner>(&bytes).err().unwrap();
+    assert!(matches!(
+        err.root(),
+        Error::Format("Sequences of sequences are not supported")
+    ));
+
+    let mut r: &[u8] = &bytes[21..];
+    assert!(matches!(
+        skip_epee_value(&mut r),
+        Err(Error::Format("Sequences of sequences are not supported"))
+    ));
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "smallvec")]
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+use smallvec::{smallvec, SmallVec};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Peer {
+    id: u64,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Small {
+    peers: SmallVec<[Peer; 2]>,
+    vals: SmallVec<[u32; 4]>,
+    blob: SmallVec<[u8; 8]>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Large {
+    peers: Vec<Peer>,
+    vals: Vec<u32>,
+    blob: Vec<u8>,
+}
+
+#[test]
+fn encoded_like_vec() {
+    let small = Small {
+        peers: smallvec![Peer { id: 1 }, Peer { id: 2 }, Peer { id: 3 }],
+        vals: smallvec![1, 2],
+        blob: smallvec![1, 2, 3],
+    };
+    let large = Large {
+        peers: vec![Peer { id: 1 }, Peer { id: 2 }, Peer { id: 3 }],
+        vals: vec![1, 2],
+        blob: vec![1, 2, 3],
+    };
+
+    let bytes = to_bytes(&small).unwrap();
+    assert_eq!(bytes, to_bytes(&large).unwrap());
+    assert_eq!(from_bytes::<Small>(&bytes).unwrap(), small);
+}
+
+#[test]
+fn empty_not_written() {
+    let small = Small {
+        peers: SmallVec::new(),
+        vals: SmallVec::new(),
+        blob: SmallVec::new(),
+    };
+
+    let bytes = to_bytes(&small).unwrap();
+    assert_eq!(from_bytes::<Small>(&bytes).unwrap(), small);
+}
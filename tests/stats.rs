@@ -0,0 +1,80 @@
+use epee_encoding::{from_bytes, from_bytes_with_stats, to_bytes, DecodeStats, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Inner {
+    val: u8,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Middle {
+    inner: Inner,
+    seq: Vec<u64>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Outer {
+    middle: Middle,
+    inners: Vec<Inner>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct OnlyInners {
+    inners: Vec<Inner>,
+}
+
+fn outer() -> Outer {
+    Outer {
+        middle: Middle {
+            inner: Inner { val: 1 },
+            seq: vec![1, 2, 3],
+        },
+        inners: vec![Inner { val: 2 }, Inner { val: 3 }],
+    }
+}
+
+#[test]
+fn stats_of_decoded_object() {
+    let val = outer();
+    let bytes = to_bytes(&val).unwrap();
+
+    let (decoded, stats) = from_bytes_with_stats::<Outer>(&bytes).unwrap();
+    assert_eq!(decoded, val);
+    assert_eq!(
+        stats,
+        DecodeStats {
+            // `middle`, `middle.inner` and the 2 `inners`.
+            objects: 4,
+            skipped_objects: 0,
+            fields: 2 + 2 + 1 + 1 + 1,
+            seq_elements: 3 + 2,
+            max_depth: 2,
+        }
+    );
+}
+
+#[test]
+fn skipped_objects_counted() {
+    let bytes = to_bytes(&outer()).unwrap();
+
+    let (_, stats) = from_bytes_with_stats::<OnlyInners>(&bytes).unwrap();
+    assert_eq!(
+        stats,
+        DecodeStats {
+            objects: 4,
+            // `middle` and `middle.inner`.
+            skipped_objects: 2,
+            fields: 2 + 2 + 1 + 1 + 1,
+            seq_elements: 3 + 2,
+            max_depth: 2,
+        }
+    );
+}
+
+#[test]
+fn trailing_bytes_rejected() {
+    let mut bytes = to_bytes(&Inner { val: 1 }).unwrap();
+    bytes.push(0);
+
+    assert!(from_bytes::<Inner>(&bytes).is_err());
+    assert!(from_bytes_with_stats::<Inner>(&bytes).is_err());
+}
@@ -0,0 +1,35 @@
+use epee_encoding::{to_bytes, write_object_streaming, EpeeObject, EpeeValueDyn};
+
+#[derive(EpeeObject)]
+struct Mixed {
+    height: u64,
+    status: String,
+    untrusted: bool,
+    txs: Vec<u32>,
+    hash: [u8; 4],
+}
+
+#[test]
+fn streaming_matches_object() {
+    let val = Mixed {
+        height: 1000,
+        status: "OK".to_string(),
+        untrusted: true,
+        txs: vec![],
+        hash: [1, 2, 3, 4],
+    };
+
+    let fields: [(&str, &dyn EpeeValueDyn); 5] = [
+        ("height", &val.height),
+        ("status", &val.status),
+        ("untrusted", &val.untrusted),
+        ("txs", &val.txs),
+        ("hash", &val.hash),
+    ];
+
+    let mut bytes = Vec::new();
+    write_object_streaming(&mut bytes, fields.into_iter()).unwrap();
+
+    // skip the header
+    assert_eq!(bytes, to_bytes(&val).unwrap()[9..]);
+}
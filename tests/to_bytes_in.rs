@@ -0,0 +1,25 @@
+use epee_encoding::{from_bytes, to_bytes, to_bytes_in, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct T {
+    val: u64,
+    name: String,
+}
+
+#[test]
+fn to_bytes_in_reuses_buffer() {
+    let mut buf = Vec::new();
+
+    for val in 0..3 {
+        let t = T {
+            val,
+            name: "name".to_string(),
+        };
+
+        buf.clear();
+        to_bytes_in(&t, &mut buf).unwrap();
+
+        assert_eq!(buf, to_bytes(&t).unwrap());
+        assert_eq!(t, from_bytes(&buf).unwrap());
+    }
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "std")]
+
+use std::io::{BufWriter, Write};
+
+use epee_encoding::{from_bytes, to_bytes, to_writer, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Message {
+    height: u64,
+    blob: Vec<u8>,
+}
+
+fn message() -> Message {
+    Message {
+        height: 100,
+        blob: vec![1; 50],
+    }
+}
+
+/// A writer which fails after `.0` bytes have been written.
+struct FailingWriter(usize);
+
+impl Write for FailingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.0 < buf.len() {
+            return Err(std::io::ErrorKind::BrokenPipe.into());
+        }
+        self.0 -= buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn to_writer_matches_to_bytes() {
+    let mut buf = Vec::new();
+    to_writer(&message(), BufWriter::new(&mut buf)).unwrap();
+
+    assert_eq!(buf, to_bytes(&message()).unwrap());
+    assert_eq!(from_bytes::<Message>(&buf).unwrap(), message());
+}
+
+#[test]
+fn to_writer_io_error() {
+    let err = to_writer(&message(), FailingWriter(20)).err().unwrap();
+
+    assert_eq!(err.to_string(), "IO error: Error writing to std::io::Write");
+}
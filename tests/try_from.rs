@@ -0,0 +1,55 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, Error};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    Inbound,
+    Outbound,
+}
+
+impl TryFrom<u8> for Direction {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Direction::Inbound),
+            1 => Ok(Direction::Outbound),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<Direction> for u8 {
+    fn from(value: Direction) -> Self {
+        value as u8
+    }
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Connection {
+    #[epee_try_from(u8)]
+    direction: Direction,
+}
+
+#[derive(EpeeObject)]
+struct RawConnection {
+    direction: u8,
+}
+
+#[test]
+fn try_from_round_trip() {
+    let val = Connection {
+        direction: Direction::Outbound,
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(bytes, to_bytes(&RawConnection { direction: 1 }).unwrap());
+    assert_eq!(from_bytes::<Connection>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn try_from_error_is_value_error() {
+    let bytes = to_bytes(&RawConnection { direction: 2 }).unwrap();
+
+    let err = from_bytes::<Connection>(&bytes).unwrap_err();
+    assert!(matches!(err.root(), Error::Value(_)));
+}
@@ -0,0 +1,66 @@
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct WithTuples {
+    pair: (u64, String),
+    triple: (u8, Vec<u32>, bool),
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct TupleStruct(u64, String);
+
+#[test]
+fn tuple_round_trip() {
+    let val = WithTuples {
+        pair: (5, "a".to_string()),
+        triple: (1, vec![2, 3], true),
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<WithTuples>(&bytes).unwrap(), val);
+
+    let val = (7_u8, (1_u64, 2_u64));
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<(u8, (u64, u64))>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn tuple_encoded_like_tuple_struct() {
+    let bytes = to_bytes(&(1_u64, "b".to_string())).unwrap();
+    assert_eq!(bytes, to_bytes(&TupleStruct(1, "b".to_string())).unwrap());
+}
+
+#[test]
+fn empty_seq_element() {
+    let val = (1_u8, Vec::<u64>::new(), false);
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<(u8, Vec<u64>, bool)>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn missing_element() {
+    let bytes = to_bytes(&(1_u8, 2_u8)).unwrap();
+    assert!(from_bytes::<(u8, u8, u8)>(&bytes).is_err());
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Empty {}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct WithUnit {
+    val: u8,
+    payload: (),
+}
+
+#[test]
+fn unit_is_empty_object() {
+    let bytes = to_bytes(&()).unwrap();
+    assert_eq!(bytes, to_bytes(&Empty {}).unwrap());
+    from_bytes::<()>(&bytes).unwrap();
+
+    let val = WithUnit {
+        val: 1,
+        payload: (),
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<WithUnit>(&bytes).unwrap(), val);
+}
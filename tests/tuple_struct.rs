@@ -0,0 +1,122 @@
+use std::collections::VecDeque;
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject, EpeeValue};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Hash([u8; 32]);
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Height(u64);
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Wrapper<T>(T);
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Block {
+    hash: Hash,
+    height: Wrapper<Height>,
+    prev: Option<Hash>,
+    txs: Wrapper<Vec<u64>>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Small(u8);
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Hashes {
+    list: Vec<Hash>,
+    pair: [Hash; 2],
+    heights: VecDeque<Height>,
+    small: Vec<Small>,
+}
+
+#[derive(EpeeObject)]
+struct RawHashes {
+    list: Vec<[u8; 32]>,
+    pair: [[u8; 32]; 2],
+    heights: VecDeque<u64>,
+    small: Vec<u8>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Pair(u64, #[epee_default(0)] u8, String);
+
+#[derive(EpeeObject)]
+struct Named {
+    #[epee_alt_name("0")]
+    first: u64,
+    #[epee_alt_name("2")]
+    third: String,
+}
+
+#[test]
+fn newtype_encoded_as_field() {
+    let val = Block {
+        hash: Hash([7; 32]),
+        height: Wrapper(Height(5)),
+        prev: None,
+        txs: Wrapper(vec![]),
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    let mut expected = vec![
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x08, // 2 fields
+        0x04, b'h', b'a', b's', b'h', 0x0a, 0x80, // 32 byte string
+    ];
+    expected.extend_from_slice(&[7; 32]);
+    expected.extend_from_slice(&[0x06, b'h', b'e', b'i', b'g', b'h', b't', 0x05]);
+    expected.extend_from_slice(&5_u64.to_le_bytes());
+    assert_eq!(bytes, expected);
+
+    assert_eq!(from_bytes::<Block>(&bytes).unwrap(), val);
+
+    let val = Block {
+        prev: Some(Hash([6; 32])),
+        txs: Wrapper(vec![1, 2]),
+        ..val
+    };
+    assert_eq!(from_bytes::<Block>(&to_bytes(&val).unwrap()).unwrap(), val);
+}
+
+#[test]
+fn newtype_sequences_encoded_as_field_sequences() {
+    let val = Hashes {
+        list: vec![Hash([1; 32]), Hash([2; 32])],
+        pair: [Hash([3; 32]), Hash([4; 32])],
+        heights: VecDeque::from(vec![Height(5), Height(6)]),
+        small: vec![Small(7), Small(8)],
+    };
+    let raw = RawHashes {
+        list: vec![[1; 32], [2; 32]],
+        pair: [[3; 32], [4; 32]],
+        heights: VecDeque::from(vec![5, 6]),
+        small: vec![7, 8],
+    };
+
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(bytes, to_bytes(&raw).unwrap());
+    assert_eq!(from_bytes::<Hashes>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn newtype_markers() {
+    assert_eq!(Hash::MARKER, <[u8; 32]>::MARKER);
+    assert_eq!(Wrapper::<Height>::MARKER, u64::MARKER);
+    assert_eq!(Wrapper::<Vec<u64>>::MARKER, Vec::<u64>::MARKER);
+    assert_eq!(Vec::<Hash>::MARKER, Vec::<[u8; 32]>::MARKER);
+    assert_eq!(Vec::<Small>::MARKER, Vec::<u8>::MARKER);
+}
+
+#[test]
+fn tuple_struct_field_names() {
+    let bytes = to_bytes(&Named {
+        first: 5,
+        third: "abc".to_string(),
+    })
+    .unwrap();
+
+    let val: Pair = from_bytes(&bytes).unwrap();
+    assert_eq!(val, Pair(5, 0, "abc".to_string()));
+
+    assert_eq!(to_bytes(&val).unwrap(), bytes);
+}
@@ -0,0 +1,87 @@
+use epee_encoding::error::{Error, Result};
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+#[epee_validate(check_heights)]
+struct GetBlocks {
+    start_height: u64,
+    end_height: u64,
+}
+
+impl GetBlocks {
+    fn check_heights(&self) -> Result<()> {
+        if self.start_height > self.end_height {
+            return Err(Error::Value("start_height is above end_height"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+#[epee_tag("type")]
+#[epee_validate(check)]
+enum Request {
+    Blocks { count: u64 },
+    Empty {},
+}
+
+impl Request {
+    fn check(&self) -> Result<()> {
+        match self {
+            Request::Blocks { count: 0 } => Err(Error::Value("count is zero")),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Parent {
+    blocks: GetBlocks,
+}
+
+#[test]
+fn valid_object_decodes() {
+    let val = GetBlocks {
+        start_height: 1,
+        end_height: 10,
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<GetBlocks>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn invalid_object_errors() {
+    let val = GetBlocks {
+        start_height: 10,
+        end_height: 1,
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert!(matches!(
+        from_bytes::<GetBlocks>(&bytes),
+        Err(Error::Value("start_height is above end_height"))
+    ));
+
+    let bytes = to_bytes(&Parent { blocks: val }).unwrap();
+    assert!(matches!(
+        from_bytes::<Parent>(&bytes).unwrap_err().root(),
+        Error::Value("start_height is above end_height")
+    ));
+}
+
+#[test]
+fn enum_validated() {
+    let bytes = to_bytes(&Request::Blocks { count: 5 }).unwrap();
+    assert_eq!(
+        from_bytes::<Request>(&bytes).unwrap(),
+        Request::Blocks { count: 5 }
+    );
+
+    let bytes = to_bytes(&Request::Blocks { count: 0 }).unwrap();
+    assert!(matches!(
+        from_bytes::<Request>(&bytes),
+        Err(Error::Value("count is zero"))
+    ));
+
+    let bytes = to_bytes(&Request::Empty {}).unwrap();
+    assert_eq!(from_bytes::<Request>(&bytes).unwrap(), Request::Empty {});
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+
+use epee_encoding::{from_bytes, from_bytes_value, to_bytes, EpeeObject, InnerMarker, Value};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Child {
+    a: i8,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Typed {
+    height: u64,
+    status: String,
+    hashes: Vec<[u8; 4]>,
+    child: Child,
+    children: Vec<Child>,
+    ratio: f64,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct WithValue {
+    height: u64,
+    child: Value,
+    extra: Option<Value>,
+}
+
+fn typed() -> Typed {
+    Typed {
+        height: 10,
+        status: "OK".to_string(),
+        hashes: vec![[1; 4], [2; 4]],
+        child: Child { a: -1 },
+        children: vec![Child { a: 2 }],
+        ratio: 0.5,
+    }
+}
+
+#[test]
+fn decode_unknown_layout() {
+    let bytes = to_bytes(&typed()).unwrap();
+    let val = from_bytes_value(&bytes).unwrap();
+
+    let fields = match val {
+        Value::Object(fields) => fields,
+        _ => panic!("Top level value is not an object"),
+    };
+
+    assert_eq!(fields["height"], Value::U64(10));
+    assert_eq!(fields["status"], Value::String(b"OK".to_vec()));
+    assert_eq!(
+        fields["hashes"],
+        Value::Seq(
+            InnerMarker::String,
+            vec![Value::String(vec![1; 4]), Value::String(vec![2; 4])]
+        )
+    );
+    assert_eq!(
+        fields["child"],
+        Value::Object(BTreeMap::from([("a".to_string(), Value::I8(-1))]))
+    );
+    assert_eq!(fields["ratio"], Value::F64(0.5));
+}
+
+#[test]
+fn value_accessors() {
+    let bytes = to_bytes(&typed()).unwrap();
+    let val = from_bytes_value(&bytes).unwrap();
+
+    assert_eq!(val.get("height").and_then(Value::as_u64), Some(10));
+    assert_eq!(val.get("height").and_then(Value::as_i64), Some(10));
+    assert_eq!(val.get("status").and_then(Value::as_str), Some("OK"));
+    assert_eq!(
+        val.get("status").and_then(Value::as_bytes),
+        Some(&b"OK"[..])
+    );
+    assert_eq!(val.get("ratio").and_then(Value::as_f64), Some(0.5));
+    assert_eq!(val.get("missing"), None);
+
+    let child = val.get("child").unwrap();
+    assert_eq!(child.as_object().unwrap().len(), 1);
+    assert_eq!(child.get("a").and_then(Value::as_i64), Some(-1));
+    // A negative value doesn't fit in a `u64`.
+    assert_eq!(child.get("a").and_then(Value::as_u64), None);
+
+    let hashes = val.get("hashes").and_then(Value::as_seq).unwrap();
+    assert_eq!(hashes.len(), 2);
+    assert_eq!(hashes[0].as_bytes(), Some(&[1; 4][..]));
+    // Strings which are not valid UTF-8 can only be accessed as bytes.
+    assert_eq!(Value::String(vec![0xff]).as_str(), None);
+
+    let children = val.get("children").and_then(Value::as_seq).unwrap();
+    assert_eq!(children[0].get("a").and_then(Value::as_u64), Some(2));
+
+    // Accessors for the wrong type return `None`.
+    assert_eq!(val.as_u64(), None);
+    assert_eq!(val.get("height").and_then(Value::as_str), None);
+    assert_eq!(val.get("height").unwrap().get("a"), None);
+    assert_eq!(Value::U64(u64::MAX).as_i64(), None);
+    assert_eq!(Value::Bool(true).as_bool(), Some(true));
+}
+
+#[test]
+fn value_field_round_trip() {
+    let val = WithValue {
+        height: 1,
+        child: Value::Seq(InnerMarker::U16, vec![Value::U16(1), Value::U16(2)]),
+        extra: Some(Value::Bool(true)),
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<WithValue>(&bytes).unwrap(), val);
+
+    let val = WithValue {
+        height: 1,
+        child: Value::Object(BTreeMap::from([("a".to_string(), Value::I8(3))])),
+        extra: None,
+    };
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<WithValue>(&bytes).unwrap(), val);
+}
+
+#[test]
+fn value_debug() {
+    assert_eq!(format!("{:?}", Value::U8(1)), "U8(1)");
+}
@@ -0,0 +1,13 @@
+use epee_encoding::varint::{read_varint, write_varint, MAX_VARINT};
+
+#[test]
+fn varint_round_trip() {
+    for number in [0, 63, 64, 16383, 16384, 1 << 30, MAX_VARINT] {
+        let mut buf = Vec::new();
+        write_varint(number, &mut buf).unwrap();
+
+        let mut r = buf.as_slice();
+        assert_eq!(read_varint(&mut r).unwrap(), number);
+        assert!(r.is_empty());
+    }
+}
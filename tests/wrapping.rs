@@ -0,0 +1,34 @@
+use std::num::Wrapping;
+
+use epee_encoding::{from_bytes, to_bytes, EpeeObject};
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Counters {
+    count: Wrapping<u64>,
+    counts: Vec<u32>,
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Plain {
+    count: u64,
+    counts: Vec<u32>,
+}
+
+#[test]
+fn wrapping_round_trip() {
+    let val = Counters {
+        count: Wrapping(u64::MAX) + Wrapping(2),
+        counts: vec![1, 2],
+    };
+    let bytes = to_bytes(&val).unwrap();
+
+    assert_eq!(from_bytes::<Counters>(&bytes).unwrap(), val);
+    assert_eq!(
+        bytes,
+        to_bytes(&Plain {
+            count: 1,
+            counts: vec![1, 2],
+        })
+        .unwrap()
+    );
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+use epee_encoding::error::{Error, Result};
+use epee_encoding::io::{Read, Write};
+use epee_encoding::{
+    from_bytes, read_epee_value, to_bytes, write_object_streaming, EpeeObject, EpeeObjectBuilder,
+    EpeeObjectWrite, EpeeValueDyn, Value,
+};
+
+/// An object with a dynamic set of fields, some of which may not be written.
+#[derive(Debug, PartialEq)]
+struct Dynamic {
+    fields: Vec<(String, Vec<u64>)>,
+}
+
+#[derive(Default)]
+struct DynamicBuilder {
+    fields: Vec<(String, Vec<u64>)>,
+}
+
+impl EpeeObjectBuilder<Dynamic> for DynamicBuilder {
+    fn add_field<R: Read>(&mut self, name: &str, r: &mut R) -> Result<bool> {
+        self.fields.push((name.to_string(), read_epee_value(r)?));
+        Ok(true)
+    }
+
+    fn finish(self) -> Result<Dynamic> {
+        Ok(Dynamic {
+            fields: self.fields,
+        })
+    }
+}
+
+impl EpeeObject for Dynamic {
+    type Builder = DynamicBuilder;
+}
+
+impl EpeeObjectWrite for Dynamic {
+    fn number_of_fields(&self) -> u64 {
+        self.fields
+            .iter()
+            .filter(|(_, val)| !val.is_empty())
+            .count() as u64
+    }
+
+    fn write_fields<W: Write>(&self, _w: &mut W) -> Result<()> {
+        Err(Error::Value("Dynamic must be written with write_object"))
+    }
+
+    fn write_object<W: Write>(&self, w: &mut W) -> Result<()> {
+        write_object_streaming(
+            w,
+            self.fields
+                .iter()
+                .map(|(name, val)| (name.as_str(), val as &dyn EpeeValueDyn)),
+        )
+    }
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct Parent {
+    child: Dynamic,
+}
+
+#[test]
+fn overridden_write_object_is_used() {
+    let val = Dynamic {
+        fields: vec![("a".to_string(), vec![1, 2]), ("b".to_string(), vec![3])],
+    };
+
+    let bytes = to_bytes(&val).unwrap();
+    assert_eq!(from_bytes::<Dynamic>(&bytes).unwrap(), val);
+
+    let parent = Parent { child: val };
+    let bytes = to_bytes(&parent).unwrap();
+    assert_eq!(from_bytes::<Parent>(&bytes).unwrap(), parent);
+}
+
+#[test]
+fn field_count_matches_written_fields() {
+    let val = Dynamic {
+        fields: vec![
+            ("a".to_string(), vec![1]),
+            ("empty".to_string(), vec![]),
+            ("b".to_string(), vec![2]),
+        ],
+    };
+
+    let bytes = to_bytes(&val).unwrap();
+    let decoded = from_bytes::<Dynamic>(&bytes).unwrap();
+    assert_eq!(decoded.fields.len() as u64, val.number_of_fields());
+}
+
+#[derive(EpeeObject, Debug, PartialEq)]
+struct CatchAll {
+    val: u64,
+    seq: Vec<u8>,
+    #[epee_catch_all]
+    other: BTreeMap<String, Value>,
+}
+
+#[test]
+fn catch_all_field_count_matches_written_fields() {
+    let mut other = BTreeMap::new();
+    other.insert("x".to_string(), Value::U8(1));
+    other.insert("empty".to_string(), Value::String(Vec::new()));
+    let val = CatchAll {
+        val: 1,
+        seq: vec![],
+        other,
+    };
+
+    let bytes = to_bytes(&val).unwrap();
+    let fields = match epee_encoding::from_bytes_value(&bytes).unwrap() {
+        Value::Object(fields) => fields,
+        _ => panic!("expected an object"),
+    };
+    assert_eq!(fields.len() as u64, val.number_of_fields());
+    assert_eq!(from_bytes::<CatchAll>(&bytes).unwrap(), val);
+}